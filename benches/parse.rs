@@ -0,0 +1,80 @@
+// benches/parse.rs
+
+use criterion::{BatchSize, Criterion, Throughput, black_box, criterion_group, criterion_main};
+
+use hl_rust_core::parser::{Intern, Symbols};
+use hl_rust_core::parser::schemas::{BookDiff, BookDiffRef, Fill, FillRef, Trade, TradeRef};
+
+const BOOK_DIFF: &str = r#"{"user":"0x31ca8395cf837de08b24da3f660e77761dfb974b","oid":112233445566,"coin":"BTC","side":"B","px":"97123.5","raw_book_diff":{"update":{"origSz":"0.01234","newSz":"0.00567"}}}"#;
+
+const TRADE: &str = r#"{"coin":"ETH","side":"A","time":"2025-06-01T12:34:56.789012345","px":"3456.7","sz":"1.2345","hash":"0x9f0d6c6a5bd1e7c4c0b8e1a4f4e0a1f2d3c4b5a69788796a5b4c3d2e1f0a9b8c","trade_dir_override":"Na","side_info":[{"user":"0x31ca8395cf837de08b24da3f660e77761dfb974b","start_pos":"12.5","oid":112233445566,"twap_id":null,"cloid":null},{"user":"0x010461c14e146ac35fe42271bdc1134ee31c703a","start_pos":"-3.25","oid":112233445567,"twap_id":null,"cloid":"0x00000000000000000000000000000001"}]}"#;
+
+const FILL: &str = r#"["0x31ca8395cf837de08b24da3f660e77761dfb974b",{"coin":"SOL","px":"187.23","sz":"15.0","side":"B","time":1748781296789,"startPosition":"0.0","dir":"Open Long","closedPnl":"0.0","hash":"0x9f0d6c6a5bd1e7c4c0b8e1a4f4e0a1f2d3c4b5a69788796a5b4c3d2e1f0a9b8c","oid":112233445566,"crossed":true,"fee":"0.9828","tid":998877665544,"feeToken":"USDC","cloid":null,"twapId":null,"builderFee":null,"builder":null}]"#;
+
+fn bench_book_diff(c: &mut Criterion) {
+    let mut group = c.benchmark_group("book_diff");
+    group.throughput(Throughput::Bytes(BOOK_DIFF.len() as u64));
+
+    group.bench_function("owned", |b| {
+        b.iter(|| sonic_rs::from_str::<BookDiff>(black_box(BOOK_DIFF)).unwrap())
+    });
+
+    group.bench_function("borrowed", |b| {
+        b.iter(|| {
+            let diff: BookDiffRef = sonic_rs::from_str(black_box(BOOK_DIFF)).unwrap();
+            black_box(diff.oid)
+        })
+    });
+
+    group.bench_function("borrowed_interned", |b| {
+        b.iter_batched_ref(
+            Symbols::new,
+            |symbols| {
+                let diff: BookDiffRef = sonic_rs::from_str(black_box(BOOK_DIFF)).unwrap();
+                BookDiff::intern(diff, symbols)
+            },
+            BatchSize::LargeInput,
+        )
+    });
+
+    group.finish();
+}
+
+fn bench_trade(c: &mut Criterion) {
+    let mut group = c.benchmark_group("trade");
+    group.throughput(Throughput::Bytes(TRADE.len() as u64));
+
+    group.bench_function("owned", |b| {
+        b.iter(|| sonic_rs::from_str::<Trade>(black_box(TRADE)).unwrap())
+    });
+
+    group.bench_function("borrowed", |b| {
+        b.iter(|| {
+            let trade: TradeRef = sonic_rs::from_str(black_box(TRADE)).unwrap();
+            black_box(trade.side_info[0].oid)
+        })
+    });
+
+    group.finish();
+}
+
+fn bench_fill(c: &mut Criterion) {
+    let mut group = c.benchmark_group("fill");
+    group.throughput(Throughput::Bytes(FILL.len() as u64));
+
+    group.bench_function("owned", |b| {
+        b.iter(|| sonic_rs::from_str::<Fill>(black_box(FILL)).unwrap())
+    });
+
+    group.bench_function("borrowed", |b| {
+        b.iter(|| {
+            let fill: FillRef = sonic_rs::from_str(black_box(FILL)).unwrap();
+            black_box(fill.data().tid)
+        })
+    });
+
+    group.finish();
+}
+
+criterion_group!(benches, bench_book_diff, bench_trade, bench_fill);
+criterion_main!(benches);
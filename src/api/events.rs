@@ -1,7 +1,5 @@
 // src/api/events.rs
 
-use sonic_rs::JsonValueTrait;
-
 use crate::parser::schemas::{BookDiff, Fill, MiscEvent, OrderStatus, SystemAction, Trade, TwapStatus};
 use crate::parser::schemas::book_diff::RawBookDiff;
 use crate::parser::schemas::misc_events::MiscEventInner;
@@ -16,8 +14,8 @@ pub fn from_book_diff(diff: &BookDiff) -> Event {
     };
 
     Event::WalletBookDiff {
-        address: diff.user.clone(),
-        coin: diff.coin.clone(),
+        address: diff.user.to_string(),
+        coin: diff.coin.to_string(),
         side: format!("{:?}", diff.side),
         price: diff.px.clone(),
         action: action.to_string(),
@@ -46,7 +44,7 @@ pub fn from_trade(trade: &Trade) -> Vec<Event> {
 pub fn from_order_status(status: &OrderStatus) -> Event {
     Event::WalletOrderStatus {
         address: status.user.clone(),
-        coin: status.order.coin.clone(),
+        coin: status.order.coin.to_string(),
         side: format!("{:?}", status.order.side),
         status: status.status.clone(),
         oid: status.order.oid,
@@ -141,11 +139,7 @@ pub fn from_misc_event(event: &MiscEvent) -> Vec<Event> {
 }
 
 pub fn from_system_action(action: &SystemAction) -> Event {
-    let action_type = action.action
-        .get("type")
-        .and_then(|v| v.as_str())
-        .unwrap_or("unknown")
-        .to_string();
+    let action_type = action.action.type_name().to_string();
 
     let raw = sonic_rs::to_string(action).unwrap_or_default();
 
@@ -6,5 +6,5 @@ pub mod queries;
 pub mod router;
 pub mod streams;
 
-pub use protocol::{Envelope, Event, Payload, Request, Response};
+pub use protocol::{BookLevel, BookOrder, Envelope, Event, OpenOrder, Payload, Request, Response};
 pub use router::Router;
\ No newline at end of file
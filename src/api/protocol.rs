@@ -21,6 +21,11 @@ pub enum Payload {
 pub enum Request {
     Ping,
     GetSpread { coin: String },
+    GetL2Book { coin: String, depth: usize, tick_grouping: Option<String> },
+    GetL4Book { coin: String, depth: usize },
+    GetUserOrders { address: String },
+    GetOrder { oid: u64 },
+    GetImpact { coin: String, side: String, size: Option<String>, notional: Option<String> },
     SubscribeWallet { address: String },
     Unsubscribe { subscription_id: String },
 }
@@ -36,6 +41,54 @@ pub enum Response {
         spread_abs: String,
         spread_pct: String,
     },
+    L2Book {
+        coin: String,
+        height: u64,
+        seq: u64,
+        bids: Vec<BookLevel>,
+        asks: Vec<BookLevel>,
+    },
+    L4Book {
+        coin: String,
+        height: u64,
+        seq: u64,
+        bids: Vec<BookOrder>,
+        asks: Vec<BookOrder>,
+    },
+    UserOrders {
+        address: String,
+        height: u64,
+        orders: Vec<OpenOrder>,
+    },
+    Order {
+        oid: u64,
+        coin: String,
+        user: String,
+        side: String,
+        price: String,
+        size: String,
+        position: usize,
+        size_ahead: String,
+        level_orders: usize,
+        level_size: String,
+        levels_ahead: usize,
+        best_price: String,
+        distance: String,
+        height: u64,
+    },
+    Impact {
+        coin: String,
+        side: String,
+        height: u64,
+        filled_size: String,
+        notional: String,
+        vwap: String,
+        best_price: String,
+        worst_price: String,
+        slippage_bps: String,
+        levels_consumed: usize,
+        complete: bool,
+    },
     Subscribed {
         subscription_id: String,
     },
@@ -45,6 +98,34 @@ pub enum Response {
     },
 }
 
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct BookLevel {
+    pub price: String,
+    pub size: String,
+    pub orders: usize,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct BookOrder {
+    pub user: String,
+    pub oid: u64,
+    pub price: String,
+    pub size: String,
+    pub timestamp: i64,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct OpenOrder {
+    pub coin: String,
+    pub side: String,
+    pub oid: u64,
+    pub price: String,
+    pub size: String,
+    pub orig_size: String,
+    pub timestamp: i64,
+    pub cloid: Option<String>,
+}
+
 #[derive(Debug, Clone, Serialize, Deserialize)]
 #[serde(tag = "kind", rename_all = "snake_case")]
 pub enum Event {
@@ -0,0 +1,121 @@
+// src/api/queries/book.rs
+
+use std::str::FromStr;
+use std::sync::Arc;
+
+use rust_decimal::Decimal;
+
+use crate::api::protocol::{BookLevel, BookOrder, Response};
+use crate::orderbook::{CoinBook, Price, PriceLevel};
+use crate::parser::schemas::common::Side;
+use super::QueryContext;
+
+const MAX_DEPTH: usize = 1000;
+
+// `tick_grouping` is a whole number of ticks per bucket (1, 10, 100, ...), using the tick the
+// book's own prices imply.
+pub fn handle_l2(ctx: &QueryContext, coin: &str, depth: usize, tick_grouping: Option<&str>) -> Response {
+    let ticks = match tick_grouping.map(Decimal::from_str) {
+        None => None,
+        Some(Ok(ticks)) if ticks >= Decimal::ONE && ticks.fract().is_zero() => Some(ticks),
+        Some(_) => {
+            return Response::Error {
+                message: format!(
+                    "invalid tick grouping {}, expected a whole number of ticks",
+                    tick_grouping.unwrap_or_default()
+                ),
+            };
+        }
+    };
+
+    let Some((book, height)) = view(ctx, coin) else {
+        return not_found(coin);
+    };
+    let depth = depth.min(MAX_DEPTH);
+
+    let bucket = match (ticks, book.tick()) {
+        (Some(ticks), Some(tick)) => match ticks.checked_mul(tick) {
+            Some(bucket) => Some(bucket.normalize()),
+            None => {
+                return Response::Error {
+                    message: format!("tick grouping {} is too large for {}", ticks, coin),
+                };
+            }
+        },
+        _ => None,
+    };
+
+    let levels = |side| -> anyhow::Result<Vec<BookLevel>> {
+        Ok(book
+            .l2(depth, side, bucket)?
+            .into_iter()
+            .map(|level| BookLevel {
+                price: level.price.to_string(),
+                size: level.size.to_string(),
+                orders: level.orders,
+            })
+            .collect())
+    };
+    let (bids, asks) = match (levels(Side::Bid), levels(Side::Ask)) {
+        (Ok(bids), Ok(asks)) => (bids, asks),
+        (Err(e), _) | (_, Err(e)) => {
+            return Response::Error {
+                message: format!("cannot group {}: {}", coin, e),
+            };
+        }
+    };
+
+    Response::L2Book {
+        coin: coin.to_string(),
+        height,
+        seq: book.seq(),
+        bids,
+        asks,
+    }
+}
+
+pub fn handle_l4(ctx: &QueryContext, coin: &str, depth: usize) -> Response {
+    let Some((book, height)) = view(ctx, coin) else {
+        return not_found(coin);
+    };
+    let depth = depth.min(MAX_DEPTH);
+
+    Response::L4Book {
+        coin: coin.to_string(),
+        height,
+        seq: book.seq(),
+        bids: orders(book.bids_desc(), depth),
+        asks: orders(book.asks_asc(), depth),
+    }
+}
+
+// A book untouched by recent blocks is still current as of the service height, which is read
+// before the book so it can never be ahead of the view.
+pub(super) fn view(ctx: &QueryContext, coin: &str) -> Option<(Arc<CoinBook>, u64)> {
+    let height = ctx.orderbook.height();
+    let book = ctx.orderbook.get(coin)?;
+    let height = height.max(book.height());
+    Some((book, height))
+}
+
+fn not_found(coin: &str) -> Response {
+    Response::Error {
+        message: format!("coin {} not found", coin),
+    }
+}
+
+// Orders at the best `depth` price levels, in queue priority within each level.
+fn orders<'a>(levels: impl Iterator<Item = (&'a Price, &'a PriceLevel)>, depth: usize) -> Vec<BookOrder> {
+    levels
+        .take(depth)
+        .flat_map(|(price, level)| {
+            level.orders().iter().map(move |entry| BookOrder {
+                user: entry.user.to_string(),
+                oid: entry.oid(),
+                price: price.to_string(),
+                size: entry.size_str().to_string(),
+                timestamp: entry.order.timestamp.as_millis(),
+            })
+        })
+        .collect()
+}
@@ -0,0 +1,65 @@
+// src/api/queries/impact.rs
+
+use std::str::FromStr;
+
+use rust_decimal::Decimal;
+
+use crate::api::protocol::Response;
+use crate::orderbook::ImpactTarget;
+use crate::parser::schemas::common::Side;
+use super::QueryContext;
+use super::book::view;
+
+pub fn handle(ctx: &QueryContext, coin: &str, side: &str, size: Option<&str>, notional: Option<&str>) -> Response {
+    let side = match side.to_ascii_lowercase().as_str() {
+        "buy" | "bid" | "b" => Side::Bid,
+        "sell" | "ask" | "a" => Side::Ask,
+        _ => return error(format!("invalid side {}, expected buy or sell", side)),
+    };
+
+    let target = match (size, notional) {
+        (Some(size), None) => parse_amount(size).map(ImpactTarget::Size),
+        (None, Some(notional)) => parse_amount(notional).map(ImpactTarget::Notional),
+        _ => return error("exactly one of size or notional is required".into()),
+    };
+    let Some(target) = target else {
+        return error(format!("invalid amount {}", size.or(notional).unwrap_or_default()));
+    };
+
+    let Some((book, height)) = view(ctx, coin) else {
+        return error(format!("coin {} not found", coin));
+    };
+
+    let impact = match book.impact(side, target) {
+        Ok(Some(impact)) => impact,
+        Ok(None) => return error(format!("no liquidity for {:?} on {}", side, coin)),
+        Err(e) => return error(format!("cannot compute impact on {}: {}", coin, e)),
+    };
+
+    Response::Impact {
+        coin: coin.to_string(),
+        side: format!("{:?}", side),
+        height,
+        filled_size: impact.filled_size.round_dp(8).normalize().to_string(),
+        notional: impact.notional.normalize().to_string(),
+        vwap: impact.vwap.round_dp(8).normalize().to_string(),
+        best_price: impact.best_price.to_string(),
+        worst_price: impact.worst_price.to_string(),
+        slippage_bps: format!("{:.4}", impact.slippage_bps),
+        levels_consumed: impact.levels_consumed,
+        complete: impact.complete,
+    }
+}
+
+// Far beyond any real order; the walk still uses checked arithmetic for whatever the book holds.
+const MAX_AMOUNT: Decimal = Decimal::from_parts(2_764_472_320, 232_830, 0, false, 0); // 1e15
+
+fn parse_amount(s: &str) -> Option<Decimal> {
+    Decimal::from_str(s)
+        .ok()
+        .filter(|d| *d > Decimal::ZERO && *d <= MAX_AMOUNT)
+}
+
+fn error(message: String) -> Response {
+    Response::Error { message }
+}
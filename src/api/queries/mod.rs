@@ -1,6 +1,10 @@
 // src/api/queries/mod.rs
 
+mod book;
+mod impact;
+mod order;
 mod spread;
+mod user_orders;
 
 use std::sync::Arc;
 
@@ -26,9 +30,110 @@ impl QueryRegistry {
         match request {
             Request::Ping => Response::Pong,
             Request::GetSpread { coin } => spread::handle(&self.ctx, &coin),
+            Request::GetL2Book { coin, depth, tick_grouping } => {
+                book::handle_l2(&self.ctx, &coin, depth, tick_grouping.as_deref())
+            }
+            Request::GetL4Book { coin, depth } => book::handle_l4(&self.ctx, &coin, depth),
+            Request::GetUserOrders { address } => user_orders::handle(&self.ctx, &address),
+            Request::GetOrder { oid } => order::handle(&self.ctx, oid),
+            Request::GetImpact { coin, side, size, notional } => {
+                impact::handle(&self.ctx, &coin, &side, size.as_deref(), notional.as_deref())
+            }
             _ => Response::Error {
                 message: "unknown query".into(),
             },
         }
     }
-}
\ No newline at end of file
+}
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::parser::schemas::{BookDiff, Timestamp};
+
+    #[test]
+    fn book_queries_report_the_same_height() {
+        let orderbook = Arc::new(OrderBookService::new());
+        let diff: BookDiff = sonic_rs::from_str(
+            r#"{"user":"0xabc","oid":1,"coin":"BTC","side":"A","px":"100","raw_book_diff":{"new":{"sz":"2"}}}"#,
+        )
+        .unwrap();
+        orderbook.apply_diff(diff, 7, Timestamp::from_millis(0));
+        orderbook.set_height(5);
+
+        let registry = QueryRegistry::new(orderbook);
+        let height = |request| match registry.handle(request) {
+            Response::L2Book { height, .. }
+            | Response::L4Book { height, .. }
+            | Response::Order { height, .. }
+            | Response::Impact { height, .. } => height,
+            other => panic!("unexpected response {:?}", other),
+        };
+
+        let coin = || "BTC".to_string();
+        assert_eq!(height(Request::GetL2Book { coin: coin(), depth: 5, tick_grouping: None }), 7);
+        assert_eq!(height(Request::GetL4Book { coin: coin(), depth: 5 }), 7);
+        assert_eq!(height(Request::GetOrder { oid: 1 }), 7);
+        assert_eq!(
+            height(Request::GetImpact { coin: coin(), side: "buy".into(), size: Some("1".into()), notional: None }),
+            7
+        );
+    }
+
+    #[test]
+    fn extreme_impact_amounts_are_rejected() {
+        let orderbook = Arc::new(OrderBookService::new());
+        let diff: BookDiff = sonic_rs::from_str(
+            r#"{"user":"0xabc","oid":1,"coin":"BTC","side":"A","px":"0.5","raw_book_diff":{"new":{"sz":"2"}}}"#,
+        )
+        .unwrap();
+        orderbook.apply_diff(diff, 1, Timestamp::from_millis(0));
+        let registry = QueryRegistry::new(orderbook);
+
+        let response = registry.handle(Request::GetImpact {
+            coin: "BTC".into(),
+            side: "buy".into(),
+            size: None,
+            notional: Some("79228162514264337593543950335".into()),
+        });
+        assert!(matches!(response, Response::Error { .. }), "{:?}", response);
+    }
+
+    #[test]
+    fn l2_groups_by_whole_ticks() {
+        let orderbook = Arc::new(OrderBookService::new());
+        for (oid, px) in [(1, "100.1"), (2, "100.9"), (3, "0.0000000000000000000000000001")] {
+            let diff: BookDiff = sonic_rs::from_str(&format!(
+                r#"{{"user":"0xabc","oid":{oid},"coin":"{coin}","side":"B","px":"{px}","raw_book_diff":{{"new":{{"sz":"1"}}}}}}"#,
+                coin = if oid == 3 { "DUST" } else { "BTC" },
+            ))
+            .unwrap();
+            orderbook.apply_diff(diff, 1, Timestamp::from_millis(0));
+        }
+        orderbook.apply_diff(
+            sonic_rs::from_str(r#"{"user":"0xabc","oid":4,"coin":"DUST","side":"B","px":"100","raw_book_diff":{"new":{"sz":"1"}}}"#)
+                .unwrap(),
+            1,
+            Timestamp::from_millis(0),
+        );
+        let registry = QueryRegistry::new(orderbook);
+        let l2 = |coin: &str, ticks: &str| {
+            registry.handle(Request::GetL2Book {
+                coin: coin.into(),
+                depth: 5,
+                tick_grouping: Some(ticks.into()),
+            })
+        };
+
+        match l2("BTC", "10") {
+            Response::L2Book { bids, .. } => {
+                assert_eq!(bids.len(), 1);
+                assert_eq!((bids[0].price.as_str(), bids[0].orders), ("100", 2));
+            }
+            other => panic!("unexpected response {:?}", other),
+        }
+        assert!(matches!(l2("BTC", "0.0000000000000000000000000001"), Response::Error { .. }));
+        assert!(matches!(l2("BTC", "2.5"), Response::Error { .. }));
+        // A 1e-28 tick makes even one tick per bucket overflow at price 100.
+        assert!(matches!(l2("DUST", "1"), Response::Error { .. }));
+    }
+}
@@ -0,0 +1,36 @@
+// src/api/queries/order.rs
+
+use crate::api::protocol::Response;
+use super::QueryContext;
+use super::book::view;
+
+pub fn handle(ctx: &QueryContext, oid: u64) -> Response {
+    // Locate within the same book the height comes from, so both describe one state.
+    let found = ctx
+        .orderbook
+        .coin_of(oid)
+        .and_then(|coin| view(ctx, &coin))
+        .and_then(|(book, height)| Some((book.locate(oid)?, height)));
+    let Some((found, height)) = found else {
+        return Response::Error {
+            message: format!("order {} not found", oid),
+        };
+    };
+
+    Response::Order {
+        oid,
+        coin: found.coin,
+        user: found.user,
+        side: format!("{:?}", found.side),
+        price: found.price.to_string(),
+        size: found.size.to_string(),
+        position: found.position,
+        size_ahead: found.size_ahead.to_string(),
+        level_orders: found.level_orders,
+        level_size: found.level_size.to_string(),
+        levels_ahead: found.levels_ahead,
+        best_price: found.best_price.to_string(),
+        distance: found.distance.to_string(),
+        height,
+    }
+}
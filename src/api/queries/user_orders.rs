@@ -0,0 +1,31 @@
+// src/api/queries/user_orders.rs
+
+use crate::api::protocol::{OpenOrder, Response};
+use super::QueryContext;
+
+pub fn handle(ctx: &QueryContext, address: &str) -> Response {
+    let address = address.to_lowercase();
+    let height = ctx.orderbook.height();
+
+    let orders = ctx
+        .orderbook
+        .orders_for_user(&address)
+        .into_iter()
+        .map(|entry| OpenOrder {
+            coin: entry.order.coin.to_string(),
+            side: format!("{:?}", entry.order.side),
+            oid: entry.order.oid,
+            price: entry.order.limit_px,
+            size: entry.order.sz,
+            orig_size: entry.order.orig_sz,
+            timestamp: entry.order.timestamp.as_millis(),
+            cloid: entry.order.cloid,
+        })
+        .collect();
+
+    Response::UserOrders {
+        address,
+        height,
+        orders,
+    }
+}
@@ -0,0 +1,109 @@
+// src/bin/drift_report.rs
+
+use std::path::{Path, PathBuf};
+
+use anyhow::{Result, anyhow, bail};
+use serde::de::DeserializeOwned;
+use tracing::warn;
+
+use hl_rust_core::parser::schemas::{Block, BookDiff, Fill, MiscEvent, OrderStatus, SystemAction, Trade, TwapStatus};
+use hl_rust_core::parser::{DriftCheck, DriftDetector, DriftReport, StreamDrift};
+use hl_rust_core::reader::{EndOfStream, Reader, parse_hour};
+
+#[tokio::main]
+async fn main() -> Result<()> {
+    tracing_subscriber::fmt::init();
+
+    let mut args: Vec<String> = std::env::args().skip(1).collect();
+    let json = match args.iter().position(|a| a == "--json") {
+        Some(i) => {
+            args.remove(i);
+            true
+        }
+        None => false,
+    };
+
+    let Some(data_dir) = args.first().map(PathBuf::from) else {
+        bail!("usage: drift_report <data dir> [start YYYYMMDD/HH] [end YYYYMMDD/HH] [--json]");
+    };
+    let start = match args.get(1) {
+        Some(s) => parse_hour(s).ok_or_else(|| anyhow!("invalid start hour {}", s))?,
+        None => 0,
+    };
+    let end = match args.get(2) {
+        Some(s) => Some(parse_hour(s).ok_or_else(|| anyhow!("invalid end hour {}", s))?),
+        None => None,
+    };
+
+    let mut report = DriftReport::default();
+    for stream in [
+        "node_trades",
+        "node_order_statuses",
+        "node_fills",
+        "node_twap_statuses",
+        "node_raw_book_diffs",
+        "node_order_statuses_by_block",
+        "node_fills_by_block",
+        "node_raw_book_diffs_by_block",
+        "misc_events",
+        "system_and_core_writer_actions",
+    ] {
+        let path = data_dir.join(stream);
+        if !path.join("hourly").is_dir() {
+            continue;
+        }
+
+        let drift = match stream {
+            "node_trades" => scan::<Trade>(&path, start, end).await,
+            "node_order_statuses" => scan::<OrderStatus>(&path, start, end).await,
+            "node_fills" => scan::<Fill>(&path, start, end).await,
+            "node_twap_statuses" => scan::<TwapStatus>(&path, start, end).await,
+            "node_raw_book_diffs" => scan::<BookDiff>(&path, start, end).await,
+            "node_order_statuses_by_block" => scan::<Block<OrderStatus>>(&path, start, end).await,
+            "node_fills_by_block" => scan::<Block<Fill>>(&path, start, end).await,
+            "node_raw_book_diffs_by_block" => scan::<Block<BookDiff>>(&path, start, end).await,
+            "misc_events" => scan::<MiscEvent>(&path, start, end).await,
+            _ => scan::<SystemAction>(&path, start, end).await,
+        };
+
+        match drift {
+            Ok(drift) => {
+                report.streams.insert(stream.to_string(), drift);
+            }
+            Err(e) => warn!("failed to scan {}: {}", stream, e),
+        }
+    }
+
+    if json {
+        println!("{}", sonic_rs::to_string_pretty(&report)?);
+    } else {
+        print!("{}", report);
+    }
+
+    if report.has_drift() {
+        std::process::exit(1);
+    }
+    Ok(())
+}
+
+async fn scan<T>(path: &Path, start: u64, end: Option<u64>) -> Result<StreamDrift>
+where
+    T: DeserializeOwned + serde::Serialize + DriftCheck,
+{
+    let mut reader = Reader::backfill(path.to_path_buf(), start, Some(end.unwrap_or(u64::MAX))).await?;
+    let mut detector = DriftDetector::<T>::new();
+
+    loop {
+        match reader.next_batch().await {
+            Ok(batch) => {
+                for event in &batch {
+                    detector.check_event(event);
+                }
+            }
+            Err(e) if e.is::<EndOfStream>() => break,
+            Err(e) => warn!("{}: {}", path.display(), e),
+        }
+    }
+
+    Ok(detector.finish())
+}
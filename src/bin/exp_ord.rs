@@ -1,12 +1,13 @@
-use anyhow::Result;
+use anyhow::{Context, Result};
 use std::sync::Arc;
 use tokio::sync::mpsc;
 use tokio_util::sync::CancellationToken;
 use tracing::{error, info};
 
 use hl_rust_core::orderbook::{OrderBookService, Sync, SyncConfig};
-use hl_rust_core::parser::schemas::BookDiff;
+use hl_rust_core::parser::schemas::BookDiffsByBlock;
 use hl_rust_core::parser::StreamReader;
+use hl_rust_core::reader::require_hourly_dir;
 
 const VOLUME_PATH: &str = "/var/lib/docker/volumes/hyperliquid_node-data/_data";
 
@@ -16,10 +17,13 @@ async fn main() -> Result<()> {
 
     let cancel = CancellationToken::new();
     let service = Arc::new(OrderBookService::new());
-    let (tx, rx) = mpsc::channel::<BookDiff>(1_000_000);
+    let (tx, rx) = mpsc::channel::<BookDiffsByBlock>(100_000);
 
-    let diff_path = format!("{}/hl/data/node_raw_book_diffs", VOLUME_PATH);
-    let mut reader = StreamReader::<BookDiff>::new(diff_path.into()).await?;
+    let diff_path = format!("{}/hl/data/node_raw_book_diffs_by_block", VOLUME_PATH);
+    require_hourly_dir(diff_path.as_ref())
+        .await
+        .context("the orderbook needs by-block book diffs; run the node with --batch-by-block")?;
+    let mut reader = StreamReader::<BookDiffsByBlock>::new(diff_path.into()).await?;
 
     let reader_cancel = cancel.clone();
     let reader_handle = tokio::spawn(async move {
@@ -32,8 +36,8 @@ async fn main() -> Result<()> {
                 }
                 result = reader.next() => {
                     match result {
-                        Ok(diff) => {
-                            if tx.send(diff).await.is_err() {
+                        Ok(block) => {
+                            if tx.send(block).await.is_err() {
                                 break;
                             }
                         }
@@ -73,10 +77,10 @@ async fn main() -> Result<()> {
                     break;
                 }
                 _ = tokio::time::sleep(tokio::time::Duration::from_secs(5)) => {
-                    if let Some(book) = api.get("BTC") {
-                        if let Some((bid, ask, spread)) = book.spread() {
-                            info!("BTC: {} / {} (spread: {})", bid, ask, spread);
-                        }
+                    if let Some(book) = api.get("BTC")
+                        && let Some((bid, ask, spread)) = book.spread()
+                    {
+                        info!("BTC: {} / {} (spread: {})", bid, ask, spread);
                     }
                 }
             }
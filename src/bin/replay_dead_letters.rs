@@ -0,0 +1,63 @@
+// src/bin/replay_dead_letters.rs
+
+use std::path::{Path, PathBuf};
+
+use anyhow::{Result, bail};
+use serde::de::DeserializeOwned;
+use tokio::fs;
+
+use hl_rust_core::parser::replay;
+use hl_rust_core::parser::schemas::{Block, BookDiff, Fill, MiscEvent, OrderStatus, SystemAction, Trade, TwapStatus};
+
+#[tokio::main]
+async fn main() -> Result<()> {
+    tracing_subscriber::fmt::init();
+
+    let args: Vec<String> = std::env::args().skip(1).collect();
+    let [stream, file] = args.as_slice() else {
+        bail!("usage: replay_dead_letters <stream> <dead-letter file>");
+    };
+    let path = PathBuf::from(file);
+
+    match stream.as_str() {
+        "node_trades" => run::<Trade>(&path).await,
+        "node_order_statuses" => run::<OrderStatus>(&path).await,
+        "node_fills" => run::<Fill>(&path).await,
+        "node_twap_statuses" => run::<TwapStatus>(&path).await,
+        "node_raw_book_diffs" => run::<BookDiff>(&path).await,
+        "node_order_statuses_by_block" => run::<Block<OrderStatus>>(&path).await,
+        "node_fills_by_block" => run::<Block<Fill>>(&path).await,
+        "node_raw_book_diffs_by_block" => run::<Block<BookDiff>>(&path).await,
+        "misc_events" => run::<MiscEvent>(&path).await,
+        "system_and_core_writer_actions" => run::<SystemAction>(&path).await,
+        other => bail!("unknown stream {}", other),
+    }
+}
+
+async fn run<T: DeserializeOwned>(path: &Path) -> Result<()> {
+    let result = replay::<T>(path).await?;
+
+    println!(
+        "{}: {} recovered, {} still failing",
+        path.display(),
+        result.recovered.len(),
+        result.failed.len()
+    );
+
+    for letter in &result.failed {
+        println!("  {}: {}", letter.position, letter.error);
+    }
+
+    if !result.failed.is_empty() {
+        let remaining = path.with_extension("remaining.jsonl");
+        let mut out = Vec::new();
+        for letter in &result.failed {
+            out.extend(sonic_rs::to_vec(letter)?);
+            out.push(b'\n');
+        }
+        fs::write(&remaining, out).await?;
+        println!("wrote still-failing lines to {}", remaining.display());
+    }
+
+    Ok(())
+}
@@ -1,35 +1,53 @@
 // src/bin/server.rs
 
-use std::path::PathBuf;
+use std::collections::HashMap;
+use std::path::{Path, PathBuf};
 use std::sync::Arc;
 
-use anyhow::Result;
+use anyhow::{Context, Result};
 use tokio::sync::mpsc;
+use tokio::time::{Duration, interval};
 use tokio_util::sync::CancellationToken;
 use tracing::{error, info, warn};
 
 use hl_rust_core::api::{self, Envelope, Event, Router};
 use hl_rust_core::orderbook::{OrderBookService, Sync, SyncConfig};
-use hl_rust_core::parser::schemas::{BookDiff, Fill, MiscEvent, OrderStatus, SystemAction, Trade, TwapStatus};
-use hl_rust_core::parser::StreamReader;
+use hl_rust_core::parser::schemas::{
+    BookDiffsByBlock, FillsByBlock, HasTimestamp, MiscEvent, OrderStatusesByBlock, SystemAction, Timestamp, Trade,
+    TwapStatus,
+};
+use hl_rust_core::parser::{
+    DeadLetterConfig, DeadLetterSink, FailureCounters, MergeConfig, MergedReader, ParseError, ParsePipeline,
+    PipelineConfig, Positioned, PositionedSource, StreamFailure, StreamReader,
+};
+use hl_rust_core::reader::{Checkpoint, CheckpointStore, EndOfStream, require_hourly_dir};
 use hl_rust_core::transport::ZmqServer;
 
 const VOLUME_PATH: &str = "/var/lib/docker/volumes/hyperliquid_node-data/_data";
 const DATA_PATH: &str = "/var/lib/docker/volumes/hyperliquid_node-data/_data/hl/data";
+const CHECKPOINT_PATH: &str = "./checkpoints";
+const CHECKPOINT_INTERVAL: Duration = Duration::from_secs(5);
+const DEAD_LETTER_PATH: &str = "./dead_letters";
+const PIPELINE_STATS_INTERVAL: Duration = Duration::from_secs(60);
 
 #[tokio::main]
 async fn main() -> Result<()> {
     tracing_subscriber::fmt::init();
 
+    require_hourly_dir(&stream_path("node_raw_book_diffs_by_block"))
+        .await
+        .context("the orderbook needs by-block book diffs; run the node with --batch-by-block")?;
+
     let cancel = CancellationToken::new();
     let orderbook = Arc::new(OrderBookService::new());
+    let failures = FailureCounters::new();
 
     let (event_tx, mut event_rx) = mpsc::unbounded_channel::<Event>();
-    let (diff_tx, diff_rx) = mpsc::channel::<BookDiff>(1_000_000);
+    let (diff_tx, diff_rx) = mpsc::channel::<BookDiffsByBlock>(100_000);
 
     spawn_sync(orderbook.clone(), diff_rx, cancel.clone());
-    spawn_book_diff_reader(diff_tx, event_tx.clone(), cancel.clone());
-    spawn_readers(event_tx, cancel.clone());
+    spawn_merged_reader(diff_tx, event_tx.clone(), failures.clone(), cancel.clone());
+    spawn_readers(event_tx, failures, cancel.clone());
 
     let mut router = Router::new(orderbook);
     let mut server = ZmqServer::bind("tcp://127.0.0.1:5555", "tcp://127.0.0.1:5556").await?;
@@ -81,7 +99,7 @@ async fn main() -> Result<()> {
 
 fn spawn_sync(
     orderbook: Arc<OrderBookService>,
-    diff_rx: mpsc::Receiver<BookDiff>,
+    diff_rx: mpsc::Receiver<BookDiffsByBlock>,
     cancel: CancellationToken,
 ) {
     tokio::spawn(async move {
@@ -101,80 +119,222 @@ fn spawn_sync(
     });
 }
 
-fn spawn_book_diff_reader(
-    diff_tx: mpsc::Sender<BookDiff>,
+enum Record {
+    OrderStatuses(OrderStatusesByBlock),
+    BookDiffs(BlockEvents),
+    Trade(Box<Trade>),
+    Fills(FillsByBlock),
+}
+
+struct BlockEvents {
+    time: Timestamp,
+    events: Vec<Event>,
+}
+
+impl HasTimestamp for BlockEvents {
+    fn timestamp(&self) -> Option<Timestamp> {
+        Some(self.time)
+    }
+}
+
+// Hands each block to the orderbook as soon as it is parsed and only its events to the merge, so
+// sync never waits on the lateness window or on checkpointed streams still catching up.
+struct BookDiffFeed {
+    pipeline: ParsePipeline<BookDiffsByBlock>,
+    diff_tx: mpsc::Sender<BookDiffsByBlock>,
+}
+
+impl PositionedSource<BlockEvents> for BookDiffFeed {
+    async fn next_positioned(&mut self) -> Result<Positioned<BlockEvents>> {
+        let Positioned { position, value: block } = self.pipeline.next_positioned().await?;
+        let events = BlockEvents {
+            time: block.time(),
+            events: block.events().iter().map(api::events::from_book_diff).collect(),
+        };
+
+        if self.diff_tx.send(block).await.is_err() {
+            warn!("orderbook sync stopped, no longer reading book diffs");
+            return Err(EndOfStream.into());
+        }
+
+        Ok(Positioned { position, value: events })
+    }
+}
+
+fn spawn_merged_reader(
+    diff_tx: mpsc::Sender<BookDiffsByBlock>,
     event_tx: mpsc::UnboundedSender<Event>,
+    failures: FailureCounters,
     cancel: CancellationToken,
 ) {
     tokio::spawn(async move {
-        let path = PathBuf::from(format!("{}/node_raw_book_diffs", DATA_PATH));
+        let store = match CheckpointStore::open(CHECKPOINT_PATH).await {
+            Ok(s) => s,
+            Err(e) => {
+                error!("failed to open checkpoint store for merged reader: {}", e);
+                return;
+            }
+        };
 
-        let mut reader = match StreamReader::<BookDiff>::new(path).await {
-            Ok(r) => r,
+        let mut merged = MergedReader::new(MergeConfig::default());
+        let mut dead_letters = HashMap::new();
+
+        let opened = async {
+            merged.add(
+                "node_order_statuses_by_block",
+                open_resumed(&store, "node_order_statuses_by_block").await?,
+                Record::OrderStatuses,
+            );
+            // Diffs are kept by the orderbook after crossing tasks, so they are parsed borrowed and
+            // built owned with interned coins and users rather than read through `next_ref`.
+            let pipeline = StreamReader::new(stream_path("node_raw_book_diffs_by_block"))
+                .await?
+                .into_interned_pipeline(PipelineConfig::default());
+            let handle = pipeline.handle();
+            let feed = BookDiffFeed { pipeline, diff_tx };
+            merged.add("node_raw_book_diffs_by_block", feed, Record::BookDiffs);
+            merged.add("node_trades", open_resumed(&store, "node_trades").await?, |t| Record::Trade(Box::new(t)));
+            merged.add("node_fills_by_block", open_resumed(&store, "node_fills_by_block").await?, Record::Fills);
+            anyhow::Ok(handle)
+        };
+        let book_diff_pipeline = match opened.await {
+            Ok(handle) => handle,
             Err(e) => {
-                error!("failed to create book_diff reader: {}", e);
+                error!("failed to create merged reader: {}", e);
                 return;
             }
         };
 
+        for stream in merged.streams() {
+            match open_dead_letters(stream, failures.clone()).await {
+                Some(sink) => {
+                    dead_letters.insert(*stream, sink);
+                }
+                None => return,
+            }
+        }
+
+        let mut positions: HashMap<&'static str, Checkpoint> = HashMap::new();
+        let mut checkpoint_ticker = interval(CHECKPOINT_INTERVAL);
+        let mut stats_ticker = interval(PIPELINE_STATS_INTERVAL);
+
         loop {
             tokio::select! {
                 biased;
 
                 _ = cancel.cancelled() => {
-                    info!("book_diff reader shutting down");
+                    info!("merged reader shutting down");
                     break;
                 }
 
-                result = reader.next() => {
-                    match result {
-                        Ok(diff) => {
-                            let event = api::events::from_book_diff(&diff);
-                            let _ = event_tx.send(event);
+                _ = stats_ticker.tick() => {
+                    info!(
+                        "book_diff pipeline: {}; merge buffer {} records, {} late",
+                        book_diff_pipeline.stats(),
+                        merged.buffered(),
+                        merged.late_records()
+                    );
+                }
+
+                _ = checkpoint_ticker.tick() => {
+                    save_merged_checkpoints(&store, &positions).await;
+                }
 
-                            if diff_tx.send(diff).await.is_err() {
-                                break;
+                result = merged.next() => {
+                    let record = match result {
+                        Ok(record) => record,
+                        Err(e) => {
+                            let Some(failure) = e.downcast_ref::<StreamFailure>() else {
+                                warn!("merged reader error: {}", e);
+                                continue;
+                            };
+                            match dead_letters.get_mut(failure.stream) {
+                                Some(sink) => handle_stream_failure(sink, failure).await,
+                                None => warn!("{}", failure),
                             }
+                            continue;
                         }
-                        Err(e) => {
-                            warn!("book_diff reader error: {}", e);
+                    };
+
+                    positions.insert(record.stream, record.position.checkpoint());
+
+                    match record.value {
+                        Record::OrderStatuses(block) => {
+                            for status in block.events() {
+                                let _ = event_tx.send(api::events::from_order_status(status));
+                            }
+                        }
+                        Record::BookDiffs(block) => {
+                            for event in block.events {
+                                let _ = event_tx.send(event);
+                            }
+                        }
+                        Record::Trade(trade) => {
+                            for event in api::events::from_trade(&trade) {
+                                let _ = event_tx.send(event);
+                            }
+                        }
+                        Record::Fills(block) => {
+                            for fill in block.events() {
+                                let _ = event_tx.send(api::events::from_fill(fill));
+                            }
                         }
                     }
                 }
             }
         }
+
+        save_merged_checkpoints(&store, &positions).await;
     });
 }
 
-fn spawn_readers(tx: mpsc::UnboundedSender<Event>, cancel: CancellationToken) {
-    spawn_reader::<Trade>("node_trades", tx.clone(), cancel.clone(), |item| {
-        api::events::from_trade(&item)
-    });
+async fn open_resumed<T>(store: &CheckpointStore, dir: &str) -> Result<StreamReader<T>>
+where
+    T: serde::de::DeserializeOwned,
+{
+    let path = stream_path(dir);
+    let checkpoint = match store.load(&path).await {
+        Ok(c) => c,
+        Err(e) => {
+            warn!("ignoring unreadable checkpoint for {}: {}", dir, e);
+            None
+        }
+    };
+    StreamReader::resume(path, checkpoint).await
+}
 
-    spawn_reader::<OrderStatus>("node_order_statuses", tx.clone(), cancel.clone(), |item| {
-        vec![api::events::from_order_status(&item)]
-    });
+// Book diffs are always tailed from the live end, since the orderbook is rebuilt from a snapshot.
+async fn save_merged_checkpoints(store: &CheckpointStore, positions: &HashMap<&'static str, Checkpoint>) {
+    for (stream, checkpoint) in positions {
+        if *stream == "node_raw_book_diffs_by_block" {
+            continue;
+        }
+        save_checkpoint(store, &stream_path(stream), Some(checkpoint.clone()), stream).await;
+    }
+}
 
-    spawn_reader::<Fill>("node_fills", tx.clone(), cancel.clone(), |item| {
-        vec![api::events::from_fill(&item)]
-    });
+fn stream_path(dir: &str) -> PathBuf {
+    PathBuf::from(format!("{}/{}", DATA_PATH, dir))
+}
 
-    spawn_reader::<TwapStatus>("node_twap_statuses", tx.clone(), cancel.clone(), |item| {
-        vec![api::events::from_twap_status(&item)]
+fn spawn_readers(tx: mpsc::UnboundedSender<Event>, failures: FailureCounters, cancel: CancellationToken) {
+    spawn_reader::<TwapStatus>("node_twap_statuses", tx.clone(), failures.clone(), cancel.clone(), |item| {
+        vec![api::events::from_twap_status(item)]
     });
 
-    spawn_reader::<MiscEvent>("misc_events", tx.clone(), cancel.clone(), |item| {
-        api::events::from_misc_event(&item)
+    spawn_reader::<MiscEvent>("misc_events", tx.clone(), failures.clone(), cancel.clone(), |item| {
+        api::events::from_misc_event(item)
     });
 
-    spawn_reader::<SystemAction>("system_and_core_writer_actions", tx, cancel, |item| {
-        vec![api::events::from_system_action(&item)]
+    spawn_reader::<SystemAction>("system_and_core_writer_actions", tx, failures, cancel, |item| {
+        vec![api::events::from_system_action(item)]
     });
 }
 
 fn spawn_reader<T>(
     dir: &'static str,
     tx: mpsc::UnboundedSender<Event>,
+    failures: FailureCounters,
     cancel: CancellationToken,
     convert: fn(&T) -> Vec<Event>,
 )
@@ -182,9 +342,30 @@ where
     T: serde::de::DeserializeOwned + Send + 'static,
 {
     tokio::spawn(async move {
-        let path = PathBuf::from(format!("{}/{}", DATA_PATH, dir));
+        let path = stream_path(dir);
+
+        let store = match CheckpointStore::open(CHECKPOINT_PATH).await {
+            Ok(s) => s,
+            Err(e) => {
+                error!("failed to open checkpoint store for {}: {}", dir, e);
+                return;
+            }
+        };
+
+        let mut dead_letters = match open_dead_letters(dir, failures).await {
+            Some(s) => s,
+            None => return,
+        };
+
+        let checkpoint = match store.load(&path).await {
+            Ok(c) => c,
+            Err(e) => {
+                warn!("ignoring unreadable checkpoint for {}: {}", dir, e);
+                None
+            }
+        };
 
-        let mut reader = match StreamReader::<T>::new(path).await {
+        let mut reader = match StreamReader::<T>::resume(path.clone(), checkpoint).await {
             Ok(r) => r,
             Err(e) => {
                 error!("failed to create reader for {}: {}", dir, e);
@@ -192,6 +373,8 @@ where
             }
         };
 
+        let mut checkpoint_ticker = interval(CHECKPOINT_INTERVAL);
+
         loop {
             tokio::select! {
                 biased;
@@ -201,6 +384,10 @@ where
                     break;
                 }
 
+                _ = checkpoint_ticker.tick() => {
+                    save_checkpoint(&store, &path, reader.checkpoint(), dir).await;
+                }
+
                 result = reader.next() => {
                     match result {
                         Ok(item) => {
@@ -209,11 +396,71 @@ where
                             }
                         }
                         Err(e) => {
-                            warn!("{} reader error: {}", dir, e);
+                            handle_read_error(&mut dead_letters, e).await;
                         }
                     }
                 }
             }
         }
+
+        save_checkpoint(&store, &path, reader.checkpoint(), dir).await;
     });
-}
\ No newline at end of file
+}
+
+async fn save_checkpoint(
+    store: &CheckpointStore,
+    path: &Path,
+    checkpoint: Option<Checkpoint>,
+    dir: &str,
+) {
+    let Some(checkpoint) = checkpoint else {
+        return;
+    };
+
+    if let Err(e) = store.save(path, &checkpoint).await {
+        warn!("failed to save checkpoint for {}: {}", dir, e);
+    }
+}
+
+async fn open_dead_letters(stream: &str, failures: FailureCounters) -> Option<DeadLetterSink> {
+    let config = DeadLetterConfig {
+        dir: PathBuf::from(DEAD_LETTER_PATH),
+        ..Default::default()
+    };
+
+    match DeadLetterSink::open(stream, config, failures).await {
+        Ok(s) => Some(s),
+        Err(e) => {
+            error!("failed to open dead-letter sink for {}: {}", stream, e);
+            None
+        }
+    }
+}
+
+async fn handle_stream_failure(sink: &mut DeadLetterSink, failure: &StreamFailure) {
+    let Some(parse_error) = failure.error.downcast_ref::<ParseError>() else {
+        warn!("{}", failure);
+        return;
+    };
+    record_dead_letter(sink, parse_error).await;
+}
+
+async fn handle_read_error(sink: &mut DeadLetterSink, e: anyhow::Error) {
+    let Some(parse_error) = e.downcast_ref::<ParseError>() else {
+        warn!("{} reader error: {}", sink.stream(), e);
+        return;
+    };
+    record_dead_letter(sink, parse_error).await;
+}
+
+async fn record_dead_letter(sink: &mut DeadLetterSink, parse_error: &ParseError) {
+    match sink.record(parse_error).await {
+        Ok(count) => warn!("{} ({} parse failures so far)", parse_error, count),
+        Err(write_error) => error!(
+            "{}; failed to write dead letter for {}: {}",
+            parse_error,
+            sink.stream(),
+            write_error
+        ),
+    }
+}
@@ -11,6 +11,6 @@ async fn main() -> anyhow::Result<()> {
 
     loop {
         let event = reader.next_event().await?;
-        println!("{}: {}", event.source.display(), event.line);
+        println!("{}: {}", event.position.path.display(), event.line);
     }
 }
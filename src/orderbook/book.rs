@@ -1,7 +1,12 @@
 // orderbook/book.rs
 
+use anyhow::{Context, Result};
 use imbl::{HashMap as ImHashMap, OrdMap, Vector};
+use rust_decimal::Decimal;
+use std::ops::Bound::{Excluded, Unbounded};
+use std::str::FromStr;
 
+use crate::parser::intern::Symbol;
 use crate::parser::schemas::common::Side;
 
 use super::entry::OrderEntry;
@@ -10,22 +15,27 @@ use super::price::Price;
 #[derive(Debug, Clone, Default)]
 pub struct PriceLevel {
     orders: Vector<OrderEntry>,
+    total_sz: Decimal,
 }
 
 impl PriceLevel {
     pub fn new() -> Self {
         Self {
             orders: Vector::new(),
+            total_sz: Decimal::ZERO,
         }
     }
 
     pub fn push(&mut self, entry: OrderEntry) {
+        self.total_sz += parse_size(entry.size_str());
         self.orders.push_back(entry);
     }
 
     pub fn remove_by_oid(&mut self, oid: u64) -> Option<OrderEntry> {
         let idx = self.orders.iter().position(|e| e.oid() == oid)?;
-        Some(self.orders.remove(idx))
+        let entry = self.orders.remove(idx);
+        self.total_sz -= parse_size(entry.size_str());
+        Some(entry)
     }
 
     pub fn find_by_oid(&self, oid: u64) -> Option<&OrderEntry> {
@@ -35,6 +45,7 @@ impl PriceLevel {
     pub fn update_size(&mut self, oid: u64, new_sz: String) -> bool {
         for entry in self.orders.iter_mut() {
             if entry.oid() == oid {
+                self.total_sz += parse_size(&new_sz) - parse_size(entry.size_str());
                 entry.order.sz = new_sz;
                 return true;
             }
@@ -53,30 +64,68 @@ impl PriceLevel {
     pub fn len(&self) -> usize {
         self.orders.len()
     }
+
+    pub fn total_size(&self) -> Decimal {
+        self.total_sz
+    }
+}
+
+fn parse_size(sz: &str) -> Decimal {
+    Decimal::from_str(sz).unwrap_or_default()
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct L2Level {
+    pub price: Price,
+    pub size: Decimal,
+    pub orders: usize,
+}
+
+#[derive(Debug, Clone)]
+pub struct QueuePosition {
+    pub coin: String,
+    pub user: String,
+    pub oid: u64,
+    pub side: Side,
+    pub price: Price,
+    pub size: Decimal,
+    // Index in the level's FIFO; 0 is next to fill.
+    pub position: usize,
+    pub size_ahead: Decimal,
+    pub level_orders: usize,
+    pub level_size: Decimal,
+    pub levels_ahead: usize,
+    pub best_price: Price,
+    // How far the order sits behind the best price on its own side, always >= 0.
+    pub distance: Decimal,
 }
 
 #[derive(Debug, Clone)]
 struct OidLocation {
     side: Side,
     price: Price,
-    user: String,
+    user: Symbol,
 }
 
 #[derive(Debug, Clone)]
 pub struct CoinBook {
-    coin: String,
+    coin: Symbol,
     bids: OrdMap<Price, PriceLevel>,
     asks: OrdMap<Price, PriceLevel>,
     oid_index: ImHashMap<u64, OidLocation>,
+    height: u64,
+    seq: u64,
 }
 
 impl CoinBook {
-    pub fn new(coin: String) -> Self {
+    pub fn new(coin: impl Into<Symbol>) -> Self {
         Self {
-            coin,
+            coin: coin.into(),
             bids: OrdMap::new(),
             asks: OrdMap::new(),
             oid_index: ImHashMap::new(),
+            height: 0,
+            seq: 0,
         }
     }
 
@@ -84,16 +133,39 @@ impl CoinBook {
         &self.coin
     }
 
-    pub fn insert(&mut self, entry: OrderEntry) {
+    pub fn symbol(&self) -> &Symbol {
+        &self.coin
+    }
+
+    // Block height of the snapshot or last diff this book reflects.
+    pub fn height(&self) -> u64 {
+        self.height
+    }
+
+    pub fn set_height(&mut self, height: u64) {
+        self.height = height;
+    }
+
+    // Bumped on every change published for this coin, including snapshot replacements.
+    pub fn seq(&self) -> u64 {
+        self.seq
+    }
+
+    pub(crate) fn set_seq(&mut self, seq: u64) {
+        self.seq = seq;
+    }
+
+    // False if the oid is already resting or the price does not parse; the book is left as it was.
+    pub fn insert(&mut self, entry: OrderEntry) -> bool {
         let oid = entry.oid();
 
         if self.oid_index.contains_key(&oid) {
-            return;
+            return false;
         }
 
         let price = match Price::parse(entry.price_str()) {
             Some(p) => p,
-            None => return,
+            None => return false,
         };
 
         let side = entry.side();
@@ -110,6 +182,7 @@ impl CoinBook {
             .push(entry);
 
         self.oid_index.insert(oid, OidLocation { side, price, user });
+        true
     }
 
     pub fn update(&mut self, oid: u64, orig_sz: &str, new_sz: String) -> bool {
@@ -162,8 +235,67 @@ impl CoinBook {
         self.oid_index.contains_key(&oid)
     }
 
-    pub fn get_user(&self, oid: u64) -> Option<&str> {
-        self.oid_index.get(&oid).map(|loc| loc.user.as_str())
+    pub fn get(&self, oid: u64) -> Option<&OrderEntry> {
+        let loc = self.oid_index.get(&oid)?;
+        let levels = match loc.side {
+            Side::Bid => &self.bids,
+            Side::Ask => &self.asks,
+        };
+        levels.get(&loc.price)?.find_by_oid(oid)
+    }
+
+    pub fn locate(&self, oid: u64) -> Option<QueuePosition> {
+        let loc = self.oid_index.get(&oid)?;
+        let (levels, best) = match loc.side {
+            Side::Bid => (&self.bids, self.bids.get_max()),
+            Side::Ask => (&self.asks, self.asks.get_min()),
+        };
+        let level = levels.get(&loc.price)?;
+        let (best_price, _) = best?;
+
+        let position = level.orders().iter().position(|e| e.oid() == oid)?;
+        let entry = &level.orders()[position];
+        let size_ahead = level
+            .orders()
+            .iter()
+            .take(position)
+            .map(|e| parse_size(e.size_str()))
+            .sum();
+
+        let (levels_ahead, distance) = match loc.side {
+            Side::Bid => (
+                levels.range((Excluded(loc.price), Unbounded)).count(),
+                best_price.as_decimal() - loc.price.as_decimal(),
+            ),
+            Side::Ask => (
+                levels.range((Unbounded, Excluded(loc.price))).count(),
+                loc.price.as_decimal() - best_price.as_decimal(),
+            ),
+        };
+
+        Some(QueuePosition {
+            coin: self.coin.to_string(),
+            user: loc.user.to_string(),
+            oid,
+            side: loc.side,
+            price: loc.price,
+            size: parse_size(entry.size_str()),
+            position,
+            size_ahead,
+            level_orders: level.len(),
+            level_size: level.total_size(),
+            levels_ahead,
+            best_price: *best_price,
+            distance,
+        })
+    }
+
+    pub fn oids(&self) -> impl Iterator<Item = u64> + '_ {
+        self.oid_index.keys().copied()
+    }
+
+    pub fn get_user(&self, oid: u64) -> Option<&Symbol> {
+        self.oid_index.get(&oid).map(|loc| &loc.user)
     }
 
     pub fn best_bid(&self) -> Option<(&Price, &PriceLevel)> {
@@ -190,6 +322,66 @@ impl CoinBook {
         self.asks.iter()
     }
 
+    // Best-first aggregated levels. With a bucket, prices are grouped onto multiples of it, bids
+    // rounding down and asks rounding up so a bucket never looks better than the orders in it.
+    pub fn l2(&self, depth: usize, side: Side, bucket: Option<Decimal>) -> Result<Vec<L2Level>> {
+        let levels: Box<dyn Iterator<Item = (&Price, &PriceLevel)>> = match side {
+            Side::Bid => Box::new(self.bids_desc()),
+            Side::Ask => Box::new(self.asks_asc()),
+        };
+
+        let bucket = bucket.filter(|b| b.is_sign_positive() && !b.is_zero());
+        let mut out: Vec<L2Level> = Vec::with_capacity(depth.min(64));
+
+        for (price, level) in levels {
+            let price = match bucket {
+                Some(b) => {
+                    let steps = price
+                        .as_decimal()
+                        .checked_div(b)
+                        .with_context(|| format!("price {} overflows bucket {}", price, b))?;
+                    let steps = if side.is_bid() { steps.floor() } else { steps.ceil() };
+                    Price::new(
+                        steps
+                            .checked_mul(b)
+                            .with_context(|| format!("price {} overflows bucket {}", price, b))?,
+                    )
+                }
+                None => *price,
+            };
+
+            match out.last_mut() {
+                Some(last) if last.price == price => {
+                    last.size += level.total_size();
+                    last.orders += level.len();
+                }
+                _ => {
+                    if out.len() == depth {
+                        break;
+                    }
+                    out.push(L2Level {
+                        price,
+                        size: level.total_size(),
+                        orders: level.len(),
+                    });
+                }
+            }
+        }
+
+        Ok(out)
+    }
+
+    // Finest decimal step any resting price uses. The node streams carry no tick sizes, so this
+    // stands in for the coin's tick; `None` for an empty book.
+    pub fn tick(&self) -> Option<Decimal> {
+        self.bids
+            .keys()
+            .chain(self.asks.keys())
+            .map(|price| price.as_decimal().normalize().scale())
+            .max()
+            .map(|scale| Decimal::new(1, scale))
+    }
+
     pub fn total_orders(&self) -> usize {
         self.oid_index.len()
     }
@@ -208,4 +400,98 @@ impl CoinBook {
         let spread = ask_px.as_decimal() - bid_px.as_decimal();
         Some((*bid_px, *ask_px, spread))
     }
-}
\ No newline at end of file
+}
+#[cfg(test)]
+pub(super) mod tests {
+    use super::*;
+    use crate::parser::schemas::common::Order;
+    use crate::parser::schemas::timestamp::Timestamp;
+
+    pub(in crate::orderbook) fn dec(s: &str) -> Decimal {
+        Decimal::from_str(s).unwrap()
+    }
+
+    // One order per (price, size) pair, oids counting up from 1 across bids then asks.
+    pub(in crate::orderbook) fn book_with(bids: &[(&str, &str)], asks: &[(&str, &str)]) -> CoinBook {
+        let mut book = CoinBook::new("BTC");
+        let orders = bids.iter().map(|o| (Side::Bid, o)).chain(asks.iter().map(|o| (Side::Ask, o)));
+        for (oid, (side, (px, sz))) in (1..).zip(orders) {
+            let order = Order {
+                coin: "BTC".into(),
+                side,
+                limit_px: px.to_string(),
+                sz: sz.to_string(),
+                oid,
+                timestamp: Timestamp::from_millis(0),
+                trigger_condition: "N/A".into(),
+                is_trigger: false,
+                trigger_px: "0.0".to_string(),
+                children: vec![],
+                is_position_tpsl: false,
+                reduce_only: false,
+                order_type: "Limit".into(),
+                orig_sz: sz.to_string(),
+                tif: None,
+                cloid: None,
+            };
+            book.insert(OrderEntry::new(format!("0x{:040x}", oid).into(), order));
+        }
+        book
+    }
+
+    fn levels(l2: Vec<L2Level>) -> Vec<(Decimal, Decimal, usize)> {
+        l2.into_iter().map(|l| (l.price.as_decimal(), l.size, l.orders)).collect()
+    }
+
+    #[test]
+    fn l2_buckets_floor_bids_and_ceil_asks() {
+        let book = book_with(
+            &[("100.7", "1"), ("100.2", "2"), ("99.9", "3"), ("99.1", "4")],
+            &[("101.1", "1"), ("101.9", "2"), ("102", "3"), ("102.4", "4")],
+        );
+
+        assert_eq!(
+            levels(book.l2(10, Side::Bid, Some(dec("1"))).unwrap()),
+            vec![(dec("100"), dec("3"), 2), (dec("99"), dec("7"), 2)]
+        );
+        assert_eq!(
+            levels(book.l2(10, Side::Ask, Some(dec("1"))).unwrap()),
+            vec![(dec("102"), dec("6"), 3), (dec("103"), dec("4"), 1)]
+        );
+        assert_eq!(
+            levels(book.l2(10, Side::Ask, Some(dec("0.5"))).unwrap()),
+            vec![(dec("101.5"), dec("1"), 1), (dec("102"), dec("5"), 2), (dec("102.5"), dec("4"), 1)]
+        );
+    }
+
+    #[test]
+    fn l2_stops_at_depth() {
+        let book = book_with(&[("100", "1"), ("100", "2"), ("99.5", "3"), ("98", "4")], &[]);
+
+        assert_eq!(
+            levels(book.l2(2, Side::Bid, None).unwrap()),
+            vec![(dec("100"), dec("3"), 2), (dec("99.5"), dec("3"), 1)]
+        );
+        // The last bucket kept is still aggregated in full before the cutoff.
+        assert_eq!(
+            levels(book.l2(2, Side::Bid, Some(dec("2.5"))).unwrap()),
+            vec![(dec("100"), dec("3"), 2), (dec("97.5"), dec("7"), 2)]
+        );
+        assert_eq!(levels(book.l2(1, Side::Bid, Some(dec("1"))).unwrap()), vec![(dec("100"), dec("3"), 2)]);
+        assert!(book.l2(0, Side::Bid, None).unwrap().is_empty());
+        assert!(book.l2(5, Side::Ask, None).unwrap().is_empty());
+    }
+
+    #[test]
+    fn l2_overflowing_bucket_is_an_error() {
+        let book = book_with(&[("100", "1")], &[]);
+        assert!(book.l2(5, Side::Bid, Some(dec("0.0000000000000000000000000001"))).is_err());
+    }
+
+    #[test]
+    fn tick_is_the_finest_price_step() {
+        assert_eq!(book_with(&[("100.50", "1"), ("99", "1")], &[("101.25", "1")]).tick(), Some(dec("0.01")));
+        assert_eq!(book_with(&[], &[("97000", "1")]).tick(), Some(dec("1")));
+        assert_eq!(book_with(&[], &[]).tick(), None);
+    }
+}
@@ -1,18 +1,26 @@
 // orderbook/diff.rs
 
+use std::sync::LazyLock;
+
+use crate::parser::intern::Symbol;
 use crate::parser::schemas::book_diff::{BookDiff, RawBookDiff};
 use crate::parser::schemas::common::Order;
+use crate::parser::schemas::timestamp::Timestamp;
 
 use super::book::CoinBook;
 use super::entry::OrderEntry;
 
+// Diffs only ever add resting limit orders, so these are shared rather than allocated per order.
+static NO_TRIGGER: LazyLock<Symbol> = LazyLock::new(|| Symbol::from("N/A"));
+static LIMIT: LazyLock<Symbol> = LazyLock::new(|| Symbol::from("Limit"));
+
 #[derive(Debug, Clone, Copy, PartialEq, Eq)]
 pub enum ApplyResult {
     Applied,
     Skipped,
 }
 
-pub fn apply(book: &mut CoinBook, diff: BookDiff) -> ApplyResult {
+pub fn apply(book: &mut CoinBook, diff: BookDiff, time: Timestamp) -> ApplyResult {
     match diff.raw_book_diff {
         RawBookDiff::New { new } => {
             if book.contains(diff.oid) {
@@ -23,23 +31,26 @@ pub fn apply(book: &mut CoinBook, diff: BookDiff) -> ApplyResult {
                 coin: diff.coin,
                 side: diff.side,
                 limit_px: diff.px,
-                sz: new.sz,
+                sz: new.sz.clone(),
                 oid: diff.oid,
-                timestamp: 0,
-                trigger_condition: "N/A".to_string(),
+                timestamp: time,
+                trigger_condition: NO_TRIGGER.clone(),
                 is_trigger: false,
                 trigger_px: "0.0".to_string(),
                 children: vec![],
                 is_position_tpsl: false,
                 reduce_only: false,
-                order_type: "Limit".to_string(),
-                orig_sz: String::new(),
+                order_type: LIMIT.clone(),
+                orig_sz: new.sz,
                 tif: None,
                 cloid: None,
             };
 
-            book.insert(OrderEntry::new(diff.user, order));
-            ApplyResult::Applied
+            if book.insert(OrderEntry::new(diff.user, order)) {
+                ApplyResult::Applied
+            } else {
+                ApplyResult::Skipped
+            }
         }
 
         RawBookDiff::Update { update } => {
@@ -58,4 +69,30 @@ pub fn apply(book: &mut CoinBook, diff: BookDiff) -> ApplyResult {
             }
         }
     }
-}
\ No newline at end of file
+}
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::orderbook::OrderBookService;
+    use crate::parser::schemas::book_diff::NewOrder;
+    use crate::parser::schemas::common::Side;
+
+    #[test]
+    fn new_order_with_unparseable_price_is_skipped() {
+        let service = OrderBookService::new();
+        let diff = BookDiff {
+            user: "0xabc".into(),
+            oid: 1,
+            coin: "BTC".into(),
+            side: Side::Bid,
+            px: "not a price".to_string(),
+            raw_book_diff: RawBookDiff::New { new: NewOrder { sz: "1".to_string() } },
+        };
+
+        // Skipped, so the order never reaches the user and oid index either.
+        assert_eq!(service.apply_diff(diff, 1, Timestamp::from_millis(0)), ApplyResult::Skipped);
+        assert!(!service.get("BTC").unwrap().contains(1));
+        assert_eq!(service.coin_of(1), None);
+        assert!(service.orders_for_user("0xabc").is_empty());
+    }
+}
@@ -0,0 +1,159 @@
+// orderbook/divergence.rs
+
+use rust_decimal::Decimal;
+use std::fmt;
+use std::str::FromStr;
+
+use super::book::CoinBook;
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct SizeMismatch {
+    pub oid: u64,
+    pub live: String,
+    pub snapshot: String,
+}
+
+#[derive(Debug, Clone, Default)]
+pub struct CoinDivergence {
+    pub coin: String,
+    pub missing: Vec<u64>,
+    pub extra: Vec<u64>,
+    pub size_mismatches: Vec<SizeMismatch>,
+}
+
+impl CoinDivergence {
+    pub fn is_empty(&self) -> bool {
+        self.missing.is_empty() && self.extra.is_empty() && self.size_mismatches.is_empty()
+    }
+}
+
+impl fmt::Display for CoinDivergence {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(
+            f,
+            "{}: {} missing, {} extra, {} size mismatches",
+            self.coin,
+            self.missing.len(),
+            self.extra.len(),
+            self.size_mismatches.len()
+        )
+    }
+}
+
+#[derive(Debug, Clone, Default)]
+pub struct DivergenceReport {
+    pub height: u64,
+    pub coins_checked: usize,
+    pub coins: Vec<CoinDivergence>,
+}
+
+impl DivergenceReport {
+    pub fn has_divergence(&self) -> bool {
+        !self.coins.is_empty()
+    }
+}
+
+impl fmt::Display for DivergenceReport {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(
+            f,
+            "{}/{} coins diverged at height {}",
+            self.coins.len(),
+            self.coins_checked,
+            self.height
+        )?;
+        for (i, coin) in self.coins.iter().enumerate() {
+            write!(f, "{}{}", if i == 0 { " (" } else { "; " }, coin)?;
+        }
+        if self.has_divergence() {
+            write!(f, ")")?;
+        }
+        Ok(())
+    }
+}
+
+// "Missing" orders are in the snapshot but not the live book, "extra" ones the other way round.
+pub fn compare(live: &CoinBook, snapshot: &CoinBook) -> CoinDivergence {
+    let mut divergence = CoinDivergence {
+        coin: snapshot.coin().to_string(),
+        ..Default::default()
+    };
+
+    for oid in snapshot.oids() {
+        let Some(expected) = snapshot.get(oid) else {
+            continue;
+        };
+        match live.get(oid) {
+            None => divergence.missing.push(oid),
+            Some(actual) if !same_size(actual.size_str(), expected.size_str()) => {
+                divergence.size_mismatches.push(SizeMismatch {
+                    oid,
+                    live: actual.size_str().to_string(),
+                    snapshot: expected.size_str().to_string(),
+                });
+            }
+            Some(_) => {}
+        }
+    }
+
+    divergence.extra = live.oids().filter(|oid| !snapshot.contains(*oid)).collect();
+
+    divergence.missing.sort_unstable();
+    divergence.extra.sort_unstable();
+    divergence.size_mismatches.sort_unstable_by_key(|m| m.oid);
+    divergence
+}
+
+fn same_size(a: &str, b: &str) -> bool {
+    match (Decimal::from_str(a), Decimal::from_str(b)) {
+        (Ok(a), Ok(b)) => a == b,
+        _ => a == b,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::orderbook::book::tests::book_with;
+
+    #[test]
+    fn matching_books_do_not_diverge() {
+        // Sizes are compared as numbers, so trailing zeros are not a mismatch.
+        let live = book_with(&[("100", "1"), ("99", "2.50")], &[("101", "3")]);
+        let snapshot = book_with(&[("100", "1.0"), ("99", "2.5")], &[("101", "3")]);
+        assert!(compare(&live, &snapshot).is_empty());
+    }
+
+    #[test]
+    fn order_only_in_the_snapshot_is_missing() {
+        let live = book_with(&[("100", "1")], &[]);
+        let snapshot = book_with(&[("100", "1")], &[("101", "1")]);
+
+        let divergence = compare(&live, &snapshot);
+        assert_eq!(divergence.missing, [2]);
+        assert!(divergence.extra.is_empty() && divergence.size_mismatches.is_empty());
+    }
+
+    #[test]
+    fn order_only_in_the_live_book_is_extra() {
+        let live = book_with(&[("100", "1"), ("99", "1")], &[]);
+        let snapshot = book_with(&[("100", "1")], &[]);
+
+        let divergence = compare(&live, &snapshot);
+        assert_eq!(divergence.extra, [2]);
+        assert!(divergence.missing.is_empty() && divergence.size_mismatches.is_empty());
+    }
+
+    #[test]
+    fn different_size_is_a_mismatch() {
+        let live = book_with(&[("100", "1"), ("99", "4")], &[]);
+        let snapshot = book_with(&[("100", "1"), ("99", "3")], &[]);
+
+        let divergence = compare(&live, &snapshot);
+        assert_eq!(
+            divergence.size_mismatches,
+            [SizeMismatch { oid: 2, live: "4".to_string(), snapshot: "3".to_string() }]
+        );
+        assert!(divergence.missing.is_empty() && divergence.extra.is_empty());
+    }
+}
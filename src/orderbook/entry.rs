@@ -1,15 +1,16 @@
 // orderbook/entry.rs
 
+use crate::parser::intern::Symbol;
 use crate::parser::schemas::common::{Order, Side};
 
 #[derive(Debug, Clone)]
 pub struct OrderEntry {
-    pub user: String,
+    pub user: Symbol,
     pub order: Order,
 }
 
 impl OrderEntry {
-    pub fn new(user: String, order: Order) -> Self {
+    pub fn new(user: Symbol, order: Order) -> Self {
         Self { user, order }
     }
 
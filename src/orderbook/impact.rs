@@ -0,0 +1,210 @@
+// orderbook/impact.rs
+
+use anyhow::{Context, Result};
+use rust_decimal::Decimal;
+
+use crate::parser::schemas::common::Side;
+
+use super::book::{CoinBook, PriceLevel};
+use super::price::Price;
+
+const BPS: Decimal = Decimal::from_parts(10_000, 0, 0, false, 0);
+const OVERFLOW: &str = "impact amounts overflow";
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ImpactTarget {
+    Size(Decimal),
+    Notional(Decimal),
+}
+
+#[derive(Debug, Clone)]
+pub struct Impact {
+    pub side: Side,
+    pub target: ImpactTarget,
+    pub filled_size: Decimal,
+    pub notional: Decimal,
+    pub vwap: Decimal,
+    pub best_price: Price,
+    pub worst_price: Price,
+    // Positive when the average fill is worse than the touch.
+    pub slippage_bps: Decimal,
+    pub levels_consumed: usize,
+    // False when the book ran out before the target was reached.
+    pub complete: bool,
+}
+
+impl CoinBook {
+    // `side` is the taker's side: a bid (buy) walks the asks, an ask (sell) walks the bids.
+    // `Ok(None)` means there is no liquidity on that side; an error means the amounts overflow.
+    pub fn impact(&self, side: Side, target: ImpactTarget) -> Result<Option<Impact>> {
+        match side {
+            Side::Bid => walk(self.asks_asc(), side, target),
+            Side::Ask => walk(self.bids_desc(), side, target),
+        }
+    }
+}
+
+fn walk<'a>(
+    levels: impl Iterator<Item = (&'a Price, &'a PriceLevel)>,
+    side: Side,
+    target: ImpactTarget,
+) -> Result<Option<Impact>> {
+    let mut filled_size = Decimal::ZERO;
+    let mut notional = Decimal::ZERO;
+    let mut best_price = None;
+    let mut worst_price = None;
+    let mut levels_consumed = 0;
+    let mut complete = false;
+
+    for (price, level) in levels {
+        let px = price.as_decimal();
+        let available = level.total_size();
+        if available <= Decimal::ZERO {
+            continue;
+        }
+
+        let take = match target {
+            ImpactTarget::Size(size) => available.min(size - filled_size),
+            ImpactTarget::Notional(value) => available.min((value - notional).checked_div(px).context(OVERFLOW)?),
+        };
+
+        best_price.get_or_insert(*price);
+        worst_price = Some(*price);
+        levels_consumed += 1;
+        filled_size = filled_size.checked_add(take).context(OVERFLOW)?;
+        notional = take
+            .checked_mul(px)
+            .and_then(|cost| notional.checked_add(cost))
+            .context(OVERFLOW)?;
+
+        let reached = match target {
+            ImpactTarget::Size(size) => filled_size >= size,
+            ImpactTarget::Notional(value) => notional >= value,
+        };
+        if reached || take < available {
+            complete = true;
+            break;
+        }
+    }
+
+    let Some(best_price) = best_price else {
+        return Ok(None);
+    };
+    if filled_size.is_zero() {
+        return Ok(None);
+    }
+
+    let vwap = notional.checked_div(filled_size).context(OVERFLOW)?;
+    let best = best_price.as_decimal();
+    let slippage = match side {
+        Side::Bid => vwap - best,
+        Side::Ask => best - vwap,
+    };
+    let slippage_bps = if best.is_zero() {
+        Decimal::ZERO
+    } else {
+        slippage
+            .checked_div(best)
+            .and_then(|s| s.checked_mul(BPS))
+            .context(OVERFLOW)?
+    };
+
+    Ok(Some(Impact {
+        side,
+        target,
+        filled_size,
+        notional,
+        vwap,
+        best_price,
+        worst_price: worst_price.unwrap_or(best_price),
+        slippage_bps,
+        levels_consumed,
+        complete,
+    }))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::orderbook::book::tests::{book_with, dec};
+
+    fn book() -> CoinBook {
+        book_with(&[("9", "1"), ("8", "2")], &[("10", "1"), ("11", "2"), ("12", "3")])
+    }
+
+    #[test]
+    fn partial_last_level() {
+        let impact = book().impact(Side::Bid, ImpactTarget::Size(dec("2"))).unwrap().unwrap();
+
+        assert_eq!(impact.filled_size, dec("2"));
+        assert_eq!(impact.notional, dec("21"));
+        assert_eq!(impact.vwap, dec("10.5"));
+        assert_eq!(impact.best_price.as_decimal(), dec("10"));
+        assert_eq!(impact.worst_price.as_decimal(), dec("11"));
+        assert_eq!(impact.levels_consumed, 2);
+        assert!(impact.complete);
+    }
+
+    #[test]
+    fn exact_fill() {
+        let impact = book().impact(Side::Bid, ImpactTarget::Size(dec("3"))).unwrap().unwrap();
+
+        assert_eq!(impact.filled_size, dec("3"));
+        assert_eq!(impact.notional, dec("32"));
+        assert_eq!(impact.worst_price.as_decimal(), dec("11"));
+        assert_eq!(impact.levels_consumed, 2);
+        assert!(impact.complete);
+    }
+
+    #[test]
+    fn runs_out_of_book() {
+        let impact = book().impact(Side::Bid, ImpactTarget::Size(dec("10"))).unwrap().unwrap();
+
+        assert_eq!(impact.filled_size, dec("6"));
+        assert_eq!(impact.notional, dec("68"));
+        assert_eq!(impact.worst_price.as_decimal(), dec("12"));
+        assert_eq!(impact.levels_consumed, 3);
+        assert!(!impact.complete);
+
+        assert!(book_with(&[], &[]).impact(Side::Bid, ImpactTarget::Size(dec("1"))).unwrap().is_none());
+    }
+
+    #[test]
+    fn notional_target() {
+        let impact = book().impact(Side::Bid, ImpactTarget::Notional(dec("21"))).unwrap().unwrap();
+
+        assert_eq!(impact.filled_size, dec("2"));
+        assert_eq!(impact.notional, dec("21"));
+        assert_eq!(impact.levels_consumed, 2);
+        assert!(impact.complete);
+
+        let impact = book().impact(Side::Ask, ImpactTarget::Notional(dec("1000"))).unwrap().unwrap();
+        assert_eq!(impact.filled_size, dec("3"));
+        assert_eq!(impact.notional, dec("25"));
+        assert!(!impact.complete);
+    }
+
+    #[test]
+    fn slippage_is_positive_when_worse_than_touch_on_either_side() {
+        // Buying 2 pays 10.5 against a 10 touch; selling 2 gets 8.5 against a 9 touch.
+        let buy = book().impact(Side::Bid, ImpactTarget::Size(dec("2"))).unwrap().unwrap();
+        assert_eq!(buy.slippage_bps, dec("500"));
+
+        let sell = book().impact(Side::Ask, ImpactTarget::Size(dec("2"))).unwrap().unwrap();
+        assert_eq!(sell.vwap, dec("8.5"));
+        assert_eq!(sell.best_price.as_decimal(), dec("9"));
+        assert_eq!(sell.slippage_bps.round_dp(4), dec("555.5556"));
+
+        let touch = book().impact(Side::Ask, ImpactTarget::Size(dec("1"))).unwrap().unwrap();
+        assert!(touch.slippage_bps.is_zero());
+    }
+
+    #[test]
+    fn extreme_notional_is_an_error_not_a_panic() {
+        let book = book_with(&[], &[("0.5", "1")]);
+        assert!(book.impact(Side::Bid, ImpactTarget::Notional(Decimal::MAX)).is_err());
+
+        let book = book_with(&[], &[("2", "79228162514264337593543950335")]);
+        assert!(book.impact(Side::Bid, ImpactTarget::Size(Decimal::MAX)).is_err());
+    }
+}
@@ -0,0 +1,130 @@
+// orderbook/index.rs
+
+use dashmap::DashMap;
+use std::collections::HashSet;
+
+use crate::parser::intern::Symbol;
+
+use super::book::CoinBook;
+
+// Cross-coin lookups the per-coin books cannot answer on their own: which orders a user has
+// resting, and which coin an oid belongs to.
+#[derive(Default)]
+pub(crate) struct OrderIndex {
+    users: DashMap<Symbol, HashSet<(Symbol, u64)>>,
+    coins: DashMap<u64, Symbol>,
+}
+
+impl OrderIndex {
+    pub fn insert(&self, user: &Symbol, coin: &Symbol, oid: u64) {
+        self.users
+            .entry(user.clone())
+            .or_default()
+            .insert((coin.clone(), oid));
+        self.coins.insert(oid, coin.clone());
+    }
+
+    pub fn remove(&self, user: &Symbol, coin: &Symbol, oid: u64) {
+        self.coins.remove_if(&oid, |_, c| c == coin);
+
+        let Some(mut orders) = self.users.get_mut(user) else {
+            return;
+        };
+        orders.remove(&(coin.clone(), oid));
+        let empty = orders.is_empty();
+        drop(orders);
+
+        if empty {
+            self.users.remove_if(user, |_, orders| orders.is_empty());
+        }
+    }
+
+    pub fn replace_book(&self, old: Option<&CoinBook>, new: &CoinBook) {
+        if let Some(old) = old {
+            for oid in old.oids() {
+                if let Some(user) = old.get_user(oid) {
+                    self.remove(user, old.symbol(), oid);
+                }
+            }
+        }
+
+        for oid in new.oids() {
+            if let Some(user) = new.get_user(oid) {
+                self.insert(user, new.symbol(), oid);
+            }
+        }
+    }
+
+    pub fn coin_of(&self, oid: u64) -> Option<Symbol> {
+        self.coins.get(&oid).map(|coin| coin.clone())
+    }
+
+    pub fn for_user(&self, user: &str) -> Vec<(Symbol, u64)> {
+        self.users
+            .get(user)
+            .map(|orders| orders.iter().cloned().collect())
+            .unwrap_or_default()
+    }
+
+    pub fn users(&self) -> usize {
+        self.users.len()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::orderbook::book::tests::book_with;
+
+    fn user(oid: u64) -> Symbol {
+        format!("0x{:040x}", oid).into()
+    }
+
+    #[test]
+    fn insert_then_remove() {
+        let index = OrderIndex::default();
+        let btc = Symbol::from("BTC");
+
+        index.insert(&user(1), &btc, 1);
+        index.insert(&user(1), &btc, 2);
+        assert_eq!(index.coin_of(2), Some(btc.clone()));
+        assert_eq!(index.for_user(&user(1)).len(), 2);
+
+        index.remove(&user(1), &btc, 1);
+        assert_eq!(index.coin_of(1), None);
+        assert_eq!(index.for_user(&user(1)), [(btc.clone(), 2)]);
+
+        // A user without resting orders is dropped rather than kept as an empty set.
+        index.remove(&user(1), &btc, 2);
+        assert!(index.for_user(&user(1)).is_empty());
+        assert_eq!(index.users(), 0);
+    }
+
+    #[test]
+    fn removing_under_another_coin_keeps_the_oid() {
+        let index = OrderIndex::default();
+        index.insert(&user(1), &Symbol::from("BTC"), 1);
+        index.remove(&user(1), &Symbol::from("ETH"), 1);
+
+        assert_eq!(index.coin_of(1).as_deref(), Some("BTC"));
+        assert_eq!(index.for_user(&user(1)).len(), 1);
+    }
+
+    #[test]
+    fn replacing_a_book_rebuilds_its_entries() {
+        let index = OrderIndex::default();
+        let old = book_with(&[("100", "1"), ("99", "1")], &[("101", "1")]);
+        index.replace_book(None, &old);
+        assert_eq!(index.users(), 3);
+
+        // The resynced book only has oid 1 left.
+        let new = book_with(&[("100", "2")], &[]);
+        index.replace_book(Some(&old), &new);
+
+        assert_eq!(index.users(), 1);
+        assert_eq!(index.coin_of(1).as_deref(), Some("BTC"));
+        assert_eq!(index.coin_of(2), None);
+        assert_eq!(index.coin_of(3), None);
+        assert!(index.for_user(&user(3)).is_empty());
+    }
+}
@@ -5,11 +5,13 @@ use std::path::Path;
 use tokio::fs;
 use tokio::time::{sleep, Duration};
 
-use crate::parser::schemas::l4_snapshot::L4Snapshot;
+use crate::parser::intern::Symbols;
+use crate::parser::schemas::l4_snapshot::CoinSnapshot;
 
 use super::book::CoinBook;
 use super::entry::OrderEntry;
 use super::service::OrderBookService;
+use super::snapshot::SnapshotStream;
 
 #[derive(Serialize)]
 struct SnapshotRequest {
@@ -98,40 +100,43 @@ impl SnapshotLoader {
         }
     }
 
+    // Each coin is published as soon as it is parsed, so queries see books fill in progressively.
     pub async fn load_into(&self, service: &OrderBookService) -> Result<u64> {
-        let bytes = fs::read(&self.host_path)
-            .await
-            .with_context(|| format!("failed to read snapshot from {}", self.host_path))?;
-
-        let snapshot: L4Snapshot =
-            sonic_rs::from_slice(&bytes).context("failed to parse snapshot")?;
+        let mut snapshot = self.stream().await?;
 
-        let height = snapshot.block_height();
+        while let Some(coin_snap) = snapshot.next_coin().await? {
+            service.set(Self::build_book(&coin_snap, snapshot.height()));
+        }
 
-        for coin_snap in snapshot.coins() {
-            let coin = coin_snap.coin().to_string();
-            let mut book = CoinBook::new(coin);
+        Ok(snapshot.height())
+    }
 
-            for user_order in coin_snap.book().bids() {
-                let entry = OrderEntry::new(
-                    user_order.user().to_string(),
-                    user_order.order().clone(),
-                );
-                book.insert(entry);
-            }
+    pub async fn stream(&self) -> Result<SnapshotStream> {
+        SnapshotStream::open(&self.host_path).await
+    }
 
-            for user_order in coin_snap.book().asks() {
-                let entry = OrderEntry::new(
-                    user_order.user().to_string(),
-                    user_order.order().clone(),
-                );
-                book.insert(entry);
-            }
+    pub fn build_book(coin_snap: &CoinSnapshot, height: u64) -> CoinBook {
+        let mut book = CoinBook::new(coin_snap.coin());
+        book.set_height(height);
+        let mut symbols = Symbols::new();
+
+        for user_order in coin_snap.book().bids() {
+            let entry = OrderEntry::new(
+                symbols.address(user_order.user()),
+                user_order.order().clone(),
+            );
+            book.insert(entry);
+        }
 
-            service.set(book);
+        for user_order in coin_snap.book().asks() {
+            let entry = OrderEntry::new(
+                symbols.address(user_order.user()),
+                user_order.order().clone(),
+            );
+            book.insert(entry);
         }
 
-        Ok(height)
+        book
     }
 
     pub async fn cleanup(&self) -> Result<()> {
@@ -2,16 +2,23 @@
 
 mod book;
 mod diff;
+mod divergence;
 mod entry;
+mod impact;
+mod index;
 mod loader;
 mod price;
 mod service;
+mod snapshot;
 mod sync;
 
-pub use book::{CoinBook, PriceLevel};
+pub use book::{CoinBook, L2Level, PriceLevel, QueuePosition};
 pub use diff::ApplyResult;
+pub use divergence::{CoinDivergence, DivergenceReport, SizeMismatch, compare};
 pub use entry::OrderEntry;
+pub use impact::{Impact, ImpactTarget};
 pub use loader::SnapshotLoader;
 pub use price::Price;
 pub use service::{OrderBookService, Stats};
-pub use sync::{Sync, SyncConfig};
\ No newline at end of file
+pub use snapshot::SnapshotStream;
+pub use sync::{BlockCounts, Sync, SyncConfig, SyncKind, SyncReport};
\ No newline at end of file
@@ -1,22 +1,34 @@
 // orderbook/service.rs
 
-use arc_swap::ArcSwap;
+use arc_swap::{ArcSwap, ArcSwapOption};
 use dashmap::DashMap;
+use dashmap::mapref::entry::Entry;
 use std::sync::Arc;
+use std::sync::atomic::{AtomicU64, Ordering};
 
-use crate::parser::schemas::book_diff::BookDiff;
+use crate::parser::schemas::book_diff::{BookDiff, RawBookDiff};
+use crate::parser::schemas::Timestamp;
 
-use super::book::CoinBook;
+use super::book::{CoinBook, QueuePosition};
 use super::diff::{apply, ApplyResult};
+use super::entry::OrderEntry;
+use super::sync::SyncReport;
+use super::index::OrderIndex;
 
 pub struct OrderBookService {
     books: DashMap<String, ArcSwap<CoinBook>>,
+    height: AtomicU64,
+    last_sync: ArcSwapOption<SyncReport>,
+    index: OrderIndex,
 }
 
 impl OrderBookService {
     pub fn new() -> Self {
         Self {
             books: DashMap::new(),
+            height: AtomicU64::new(0),
+            last_sync: ArcSwapOption::empty(),
+            index: OrderIndex::default(),
         }
     }
 
@@ -24,34 +36,108 @@ impl OrderBookService {
         self.books.get(coin).map(|e| e.load_full())
     }
 
-    pub fn set(&self, book: CoinBook) {
+    pub fn set(&self, mut book: CoinBook) {
         let coin = book.coin().to_string();
-        self.books
-            .entry(coin)
-            .and_modify(|swap| swap.store(Arc::new(book.clone())))
-            .or_insert_with(|| ArcSwap::from_pointee(book));
+        match self.books.entry(coin) {
+            Entry::Occupied(entry) => {
+                let old = entry.get().load_full();
+                self.index.replace_book(Some(&old), &book);
+                book.set_seq(old.seq() + 1);
+                entry.get().store(Arc::new(book));
+            }
+            Entry::Vacant(entry) => {
+                self.index.replace_book(None, &book);
+                entry.insert(ArcSwap::from_pointee(book));
+            }
+        }
     }
 
-    pub fn apply_diff(&self, diff: BookDiff) -> ApplyResult {
+    // `height` and `time` are those of the block the diff belongs to.
+    pub fn apply_diff(&self, diff: BookDiff, height: u64, time: Timestamp) -> ApplyResult {
         let coin = diff.coin.clone();
 
-        let swap = self
-            .books
-            .entry(coin.clone())
-            .or_insert_with(|| ArcSwap::from_pointee(CoinBook::new(coin)));
+        // Looked up by `&str` first so the common case does not allocate a key.
+        let swap = match self.books.get(&*coin) {
+            Some(swap) => swap,
+            None => self
+                .books
+                .entry(coin.to_string())
+                .or_insert_with(|| ArcSwap::from_pointee(CoinBook::new(coin.clone())))
+                .downgrade(),
+        };
 
         let current = swap.load();
         let mut updated = (**current).clone();
 
-        let result = apply(&mut updated, diff);
+        let oid = diff.oid;
+        let owner = match diff.raw_book_diff {
+            RawBookDiff::New { .. } => Some((true, diff.user.clone())),
+            RawBookDiff::Remove(_) => Some((false, diff.user.clone())),
+            RawBookDiff::Update { .. } => None,
+        };
+
+        let result = apply(&mut updated, diff, time);
 
         if result == ApplyResult::Applied {
+            match owner {
+                Some((true, user)) => self.index.insert(&user, &coin, oid),
+                Some((false, user)) => self.index.remove(&user, &coin, oid),
+                None => {}
+            }
+            updated.set_height(height);
+            updated.set_seq(current.seq() + 1);
             swap.store(Arc::new(updated));
         }
 
         result
     }
 
+    // Height of the last block fully applied to the books; 0 until the first snapshot is loaded.
+    pub fn height(&self) -> u64 {
+        self.height.load(Ordering::Acquire)
+    }
+
+    pub(crate) fn set_height(&self, height: u64) {
+        self.height.store(height, Ordering::Release);
+    }
+
+    pub fn last_sync(&self) -> Option<Arc<SyncReport>> {
+        self.last_sync.load_full()
+    }
+
+    pub(crate) fn record_sync(&self, report: SyncReport) {
+        self.last_sync.store(Some(Arc::new(report)));
+    }
+
+    // Resting orders of `user` across every coin, ordered by coin then oid.
+    pub fn orders_for_user(&self, user: &str) -> Vec<OrderEntry> {
+        let mut keys = self.index.for_user(user);
+        keys.sort_unstable();
+
+        let mut orders = Vec::with_capacity(keys.len());
+        let mut book: Option<Arc<CoinBook>> = None;
+
+        for (coin, oid) in keys {
+            if book.as_ref().is_none_or(|b| b.symbol() != &coin) {
+                book = self.get(&coin);
+            }
+            if let Some(entry) = book.as_ref().and_then(|b| b.get(oid)) {
+                orders.push(entry.clone());
+            }
+        }
+
+        orders
+    }
+
+    pub fn coin_of(&self, oid: u64) -> Option<String> {
+        self.index.coin_of(oid).map(|coin| coin.to_string())
+    }
+
+    pub fn locate(&self, oid: u64) -> Option<QueuePosition> {
+        let coin = self.index.coin_of(oid)?;
+        self.get(&coin)?.locate(oid)
+    }
+
     pub fn coins(&self) -> Vec<String> {
         self.books.iter().map(|r| r.key().clone()).collect()
     }
@@ -78,6 +164,7 @@ impl OrderBookService {
 
         Stats {
             books: self.books.len(),
+            users: self.index.users(),
             total_orders,
             bid_levels,
             ask_levels,
@@ -94,6 +181,7 @@ impl Default for OrderBookService {
 #[derive(Debug, Clone)]
 pub struct Stats {
     pub books: usize,
+    pub users: usize,
     pub total_orders: usize,
     pub bid_levels: usize,
     pub ask_levels: usize,
@@ -0,0 +1,278 @@
+// orderbook/snapshot.rs
+
+use anyhow::{Context, Result, bail};
+use std::path::Path;
+use tokio::fs::File;
+use tokio::io::{AsyncBufReadExt, BufReader};
+
+use crate::parser::schemas::l4_snapshot::CoinSnapshot;
+
+const READ_BUFFER: usize = 1 << 20;
+
+// Walks `[height, [[coin, [bids, asks]], ...]]` one coin at a time, so only the coin being
+// parsed is ever held in memory.
+pub struct SnapshotStream {
+    reader: BufReader<File>,
+    height: u64,
+    element: Vec<u8>,
+    coins: usize,
+    done: bool,
+}
+
+impl SnapshotStream {
+    pub async fn open(path: impl AsRef<Path>) -> Result<Self> {
+        let path = path.as_ref();
+        let file = File::open(path)
+            .await
+            .with_context(|| format!("failed to open snapshot {}", path.display()))?;
+        Self::from_file(file, READ_BUFFER).await
+    }
+
+    async fn from_file(file: File, capacity: usize) -> Result<Self> {
+        let mut stream = Self {
+            reader: BufReader::with_capacity(capacity, file),
+            height: 0,
+            element: Vec::new(),
+            coins: 0,
+            done: false,
+        };
+
+        stream.expect(b'[').await?;
+        stream.height = stream.read_height().await?;
+        stream.expect(b',').await?;
+        stream.expect(b'[').await?;
+
+        Ok(stream)
+    }
+
+    pub fn height(&self) -> u64 {
+        self.height
+    }
+
+    pub fn coins_read(&self) -> usize {
+        self.coins
+    }
+
+    pub async fn next_coin(&mut self) -> Result<Option<CoinSnapshot>> {
+        if self.done {
+            return Ok(None);
+        }
+
+        let mut token = self.peek_token().await?;
+        if token == b',' && self.coins > 0 {
+            self.reader.consume(1);
+            token = self.peek_token().await?;
+        }
+
+        match token {
+            b']' => {
+                self.reader.consume(1);
+                self.done = true;
+                Ok(None)
+            }
+            b'[' => {
+                self.read_element().await?;
+                let coin = sonic_rs::from_slice(&self.element)
+                    .with_context(|| format!("failed to parse coin {} of snapshot", self.coins))?;
+                self.coins += 1;
+                Ok(Some(coin))
+            }
+            other => bail!("unexpected {:?} in snapshot after {} coins", other as char, self.coins),
+        }
+    }
+
+    async fn peek_token(&mut self) -> Result<u8> {
+        loop {
+            let buf = self.reader.fill_buf().await?;
+            if buf.is_empty() {
+                bail!("snapshot truncated after {} coins", self.coins);
+            }
+
+            match buf.iter().position(|b| !b.is_ascii_whitespace()) {
+                Some(i) => {
+                    let token = buf[i];
+                    self.reader.consume(i);
+                    return Ok(token);
+                }
+                None => {
+                    let len = buf.len();
+                    self.reader.consume(len);
+                }
+            }
+        }
+    }
+
+    async fn expect(&mut self, expected: u8) -> Result<()> {
+        let token = self.peek_token().await?;
+        if token != expected {
+            bail!("expected {:?} in snapshot header, found {:?}", expected as char, token as char);
+        }
+        self.reader.consume(1);
+        Ok(())
+    }
+
+    async fn read_height(&mut self) -> Result<u64> {
+        let mut height: Option<u64> = None;
+        self.peek_token().await?;
+
+        loop {
+            let buf = self.reader.fill_buf().await?;
+            let digits = buf.iter().take_while(|b| b.is_ascii_digit()).count();
+
+            for &b in &buf[..digits] {
+                height = height
+                    .unwrap_or(0)
+                    .checked_mul(10)
+                    .and_then(|h| h.checked_add(u64::from(b - b'0')));
+                if height.is_none() {
+                    bail!("snapshot height out of range");
+                }
+            }
+
+            let finished = digits < buf.len() || buf.is_empty();
+            self.reader.consume(digits);
+            if finished {
+                break;
+            }
+        }
+
+        height.context("snapshot does not start with a block height")
+    }
+
+    // Copies one complete JSON array, starting at the current '[', into `element`.
+    async fn read_element(&mut self) -> Result<()> {
+        self.element.clear();
+        let mut depth = 0usize;
+        let mut in_string = false;
+        let mut escaped = false;
+
+        loop {
+            let buf = self.reader.fill_buf().await?;
+            if buf.is_empty() {
+                bail!("snapshot truncated inside coin {}", self.coins);
+            }
+
+            let mut end = None;
+            for (i, &b) in buf.iter().enumerate() {
+                if in_string {
+                    if escaped {
+                        escaped = false;
+                    } else if b == b'\\' {
+                        escaped = true;
+                    } else if b == b'"' {
+                        in_string = false;
+                    }
+                    continue;
+                }
+
+                match b {
+                    b'"' => in_string = true,
+                    b'[' | b'{' => depth += 1,
+                    b']' | b'}' => {
+                        depth -= 1;
+                        if depth == 0 {
+                            end = Some(i + 1);
+                            break;
+                        }
+                    }
+                    _ => {}
+                }
+            }
+
+            let len = end.unwrap_or(buf.len());
+            self.element.extend_from_slice(&buf[..len]);
+            self.reader.consume(len);
+
+            if end.is_some() {
+                return Ok(());
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const CAPACITIES: [usize; 5] = [1, 2, 3, 7, READ_BUFFER];
+
+    fn order(coin: &str, oid: u64, cloid: &str) -> String {
+        format!(
+            r#"["0xabc",{{"coin":{coin},"side":"B","limitPx":"1.5","sz":"2","oid":{oid},"timestamp":1700000000000,"triggerCondition":"N/A","isTrigger":false,"triggerPx":"0.0","children":[],"isPositionTpsl":false,"reduceOnly":false,"orderType":"Limit","origSz":"2","tif":"Gtc","cloid":{cloid}}}]"#
+        )
+    }
+
+    fn coin(name: &str, oid: u64, cloid: &str) -> String {
+        format!("[{name}, [[{}], []]]", order(name, oid, cloid))
+    }
+
+    async fn read_all(body: &str, capacity: usize) -> Result<(u64, Vec<(String, u64)>)> {
+        let path = std::env::temp_dir().join(format!("hl-snapshot-{}", uuid::Uuid::new_v4()));
+        tokio::fs::write(&path, body).await?;
+        let result = async {
+            let mut stream = SnapshotStream::from_file(File::open(&path).await?, capacity).await?;
+            let mut coins = Vec::new();
+            while let Some(coin) = stream.next_coin().await? {
+                coins.push((coin.coin().to_string(), coin.book().bids()[0].order().oid));
+            }
+            anyhow::Ok((stream.height(), coins))
+        }
+        .await;
+        tokio::fs::remove_file(&path).await?;
+        result
+    }
+
+    #[tokio::test]
+    async fn reads_header_and_coins_across_refills() {
+        let body = format!(
+            " [ 123456789 ,\n [ {} ,\n{} ] ]\n",
+            coin(r#""BTC""#, 1, "null"),
+            coin(r#""ETH""#, 2, "null")
+        );
+        for capacity in CAPACITIES {
+            let (height, coins) = read_all(&body, capacity).await.unwrap();
+            assert_eq!(height, 123456789, "capacity {}", capacity);
+            assert_eq!(coins, vec![("BTC".to_string(), 1), ("ETH".to_string(), 2)], "capacity {}", capacity);
+        }
+    }
+
+    #[tokio::test]
+    async fn ignores_brackets_and_escaped_quotes_inside_strings() {
+        let body = format!(
+            "[7,[{},{},{}]]",
+            coin(r#""A]B""#, 1, r#""0x]]""#),
+            coin(r#""Q\"]""#, 2, r#""\"[{""#),
+            coin(r#""S\\""#, 3, r#""x\\""#)
+        );
+        for capacity in CAPACITIES {
+            let (height, coins) = read_all(&body, capacity).await.unwrap();
+            assert_eq!(height, 7);
+            assert_eq!(
+                coins,
+                vec![("A]B".to_string(), 1), ("Q\"]".to_string(), 2), ("S\\".to_string(), 3)],
+                "capacity {}",
+                capacity
+            );
+        }
+    }
+
+    #[tokio::test]
+    async fn empty_coin_list() {
+        for capacity in CAPACITIES {
+            let (height, coins) = read_all("[42, [ ]]", capacity).await.unwrap();
+            assert_eq!(height, 42);
+            assert!(coins.is_empty());
+        }
+    }
+
+    #[tokio::test]
+    async fn truncated_snapshot_is_an_error() {
+        let full = format!("[9,[{},{}]]", coin(r#""BTC""#, 1, "null"), coin(r#""ETH""#, 2, "null"));
+        for cut in [0, 2, 4, full.len() / 2, full.len() - 3, full.len() - 2] {
+            for capacity in CAPACITIES {
+                let error = read_all(&full[..cut], capacity).await.unwrap_err();
+                assert!(error.to_string().contains("truncated"), "cut {}: {}", cut, error);
+            }
+        }
+    }
+}
@@ -1,14 +1,20 @@
 use anyhow::Result;
+use chrono::{DateTime, Utc};
+use std::collections::HashSet;
+use std::fmt;
 use std::sync::Arc;
 use tokio::sync::mpsc;
-use tokio::time::{Duration, Instant, interval};
+use tokio::time::{Duration, Instant, interval, timeout};
 use tracing::{debug, error, info, warn};
 
-use crate::parser::schemas::book_diff::BookDiff;
+use crate::parser::schemas::BookDiffsByBlock;
 
+use super::book::CoinBook;
 use super::diff::ApplyResult;
+use super::divergence::{DivergenceReport, compare};
 use super::loader::SnapshotLoader;
 use super::service::OrderBookService;
+use super::snapshot::SnapshotStream;
 
 pub struct SyncConfig {
     pub info_url: String,
@@ -36,17 +42,93 @@ impl Default for SyncConfig {
     }
 }
 
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum SyncKind {
+    Initial,
+    Resync,
+}
+
+#[derive(Debug, Clone, Copy, Default)]
+pub struct BlockCounts {
+    pub blocks_applied: u64,
+    pub blocks_discarded: u64,
+    pub blocks_missing: u64,
+    pub diffs_applied: u64,
+    pub diffs_skipped: u64,
+    pub diffs_discarded: u64,
+}
+
+impl fmt::Display for BlockCounts {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(
+            f,
+            "{} blocks applied ({} diffs, {} skipped), {} discarded ({} diffs), {} missing",
+            self.blocks_applied,
+            self.diffs_applied,
+            self.diffs_skipped,
+            self.blocks_discarded,
+            self.diffs_discarded,
+            self.blocks_missing
+        )
+    }
+}
+
+#[derive(Debug, Clone)]
+pub struct SyncReport {
+    pub kind: SyncKind,
+    pub previous_height: u64,
+    pub snapshot_height: u64,
+    pub height: u64,
+    pub buffered_blocks: u64,
+    pub counts: BlockCounts,
+    pub divergence: Option<DivergenceReport>,
+    pub elapsed: Duration,
+    pub completed_at: DateTime<Utc>,
+}
+
+impl SyncReport {
+    // Every diff after the snapshot was applied, in height order, with none missing or rejected.
+    pub fn is_consistent(&self) -> bool {
+        self.counts.blocks_missing == 0
+            && self.counts.diffs_skipped == 0
+            && !self.divergence.as_ref().is_some_and(DivergenceReport::has_divergence)
+    }
+}
+
+impl fmt::Display for SyncReport {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(
+            f,
+            "{} at snapshot height {} (was {}, now {}) in {:?}: {} buffered, {}",
+            match self.kind {
+                SyncKind::Initial => "initial sync",
+                SyncKind::Resync => "resync",
+            },
+            self.snapshot_height,
+            self.previous_height,
+            self.height,
+            self.elapsed,
+            self.buffered_blocks,
+            self.counts
+        )?;
+        if let Some(divergence) = &self.divergence {
+            write!(f, "; {}", divergence)?;
+        }
+        Ok(())
+    }
+}
+
 pub struct Sync {
     config: SyncConfig,
     service: Arc<OrderBookService>,
-    rx: mpsc::Receiver<BookDiff>,
+    rx: mpsc::Receiver<BookDiffsByBlock>,
 }
 
 impl Sync {
     pub fn new(
         config: SyncConfig,
         service: Arc<OrderBookService>,
-        rx: mpsc::Receiver<BookDiff>,
+        rx: mpsc::Receiver<BookDiffsByBlock>,
     ) -> Self {
         Self { config, service, rx }
     }
@@ -54,45 +136,43 @@ impl Sync {
     pub async fn run(mut self) -> Result<()> {
         info!("orderbook sync starting");
 
-        self.initial_sync().await?;
+        let report = self.initial_sync().await?;
+        self.finish(report);
 
         info!("entering live mode with periodic resync every {:?}", self.config.resync_interval);
 
         let mut resync_ticker = interval(self.config.resync_interval);
         resync_ticker.reset();
 
-        let mut live_applied = 0u64;
-        let mut live_skipped = 0u64;
+        let mut live = BlockCounts::default();
         let mut last_stats_log = Instant::now();
 
         loop {
             tokio::select! {
                 biased;
 
-                Some(diff) = self.rx.recv() => {
-                    match self.service.apply_diff(diff) {
-                        ApplyResult::Applied => live_applied += 1,
-                        ApplyResult::Skipped => live_skipped += 1,
-                    }
+                Some(block) = self.rx.recv() => {
+                    self.apply_block(block, &mut live);
 
                     if last_stats_log.elapsed() > Duration::from_secs(10) {
                         let stats = self.service.stats();
                         info!(
-                            "live: {}/s applied, {}/s skipped | {} books, {} orders",
-                            live_applied / 10,
-                            live_skipped / 10,
+                            "live at height {}: {} | {} books, {} orders",
+                            self.service.height(),
+                            live,
                             stats.books,
                             stats.total_orders
                         );
-                        live_applied = 0;
-                        live_skipped = 0;
+                        live = BlockCounts::default();
                         last_stats_log = Instant::now();
                     }
                 }
 
                 _ = resync_ticker.tick() => {
-                    if let Err(e) = self.periodic_resync().await {
-                        warn!("periodic resync failed: {}, will retry next interval", e);
+                    match self.periodic_resync().await {
+                        Ok(Some(report)) => self.finish(report),
+                        Ok(None) => {}
+                        Err(e) => warn!("periodic resync failed: {}, will retry next interval", e),
                     }
                 }
 
@@ -106,7 +186,8 @@ impl Sync {
         Ok(())
     }
 
-    async fn initial_sync(&mut self) -> Result<()> {
+    async fn initial_sync(&mut self) -> Result<SyncReport> {
+        let started = Instant::now();
         let loader = SnapshotLoader::new(
             &self.config.info_url,
             &self.config.container_snapshot_path,
@@ -116,19 +197,18 @@ impl Sync {
         loader.cleanup().await.ok();
         loader.request().await?;
 
-        info!("snapshot requested, buffering diffs");
+        info!("snapshot requested, buffering diff blocks");
 
-        let mut buffer = Vec::with_capacity(500_000);
-        let start = Instant::now();
+        let mut buffer = Vec::with_capacity(10_000);
 
         loop {
             tokio::select! {
                 biased;
 
-                Some(diff) = self.rx.recv() => {
-                    buffer.push(diff);
-                    if buffer.len() % 50_000 == 0 {
-                        debug!("buffered {} diffs", buffer.len());
+                Some(block) = self.rx.recv() => {
+                    buffer.push(block);
+                    if buffer.len() % 1_000 == 0 {
+                        debug!("buffered {} blocks", buffer.len());
                     }
                 }
 
@@ -136,7 +216,7 @@ impl Sync {
                     if loader.wait(Duration::from_millis(10)).await.is_ok() {
                         break;
                     }
-                    if start.elapsed() > self.config.snapshot_timeout {
+                    if started.elapsed() > self.config.snapshot_timeout {
                         error!("snapshot timeout");
                         anyhow::bail!("snapshot timeout");
                     }
@@ -144,9 +224,10 @@ impl Sync {
             }
         }
 
-        info!("snapshot ready, buffered {} diffs", buffer.len());
+        info!("snapshot ready, buffered {} blocks", buffer.len());
 
         let height = loader.load_into(&self.service).await?;
+        self.service.set_height(height);
         let stats = self.service.stats();
 
         info!(
@@ -156,30 +237,31 @@ impl Sync {
 
         loader.cleanup().await.ok();
 
-        info!("draining buffer");
-        let drain_start = Instant::now();
-        let mut applied = 0usize;
-        let mut skipped = 0usize;
-
-        for diff in buffer {
-            match self.service.apply_diff(diff) {
-                ApplyResult::Applied => applied += 1,
-                ApplyResult::Skipped => skipped += 1,
-            }
+        let buffered_blocks = buffer.len() as u64;
+        let mut counts = BlockCounts::default();
+        for block in buffer {
+            self.apply_block(block, &mut counts);
         }
 
-        info!(
-            "buffer drained in {:?}: applied={}, skipped={}",
-            drain_start.elapsed(),
-            applied,
-            skipped
-        );
-
-        Ok(())
+        Ok(SyncReport {
+            kind: SyncKind::Initial,
+            previous_height: 0,
+            snapshot_height: height,
+            height: self.service.height(),
+            buffered_blocks,
+            counts,
+            divergence: None,
+            elapsed: started.elapsed(),
+            completed_at: Utc::now(),
+        })
     }
 
-    async fn periodic_resync(&self) -> Result<()> {
+    // Diff blocks keep queueing on the channel while the snapshot is produced. They are applied up
+    // to the snapshot height so the live books and the snapshot describe the same block, then only
+    // the coins that differ are replaced.
+    async fn periodic_resync(&mut self) -> Result<Option<SyncReport>> {
         debug!("starting periodic resync");
+        let started = Instant::now();
 
         let loader = SnapshotLoader::new(
             &self.config.info_url,
@@ -191,16 +273,285 @@ impl Sync {
         loader.request().await?;
         loader.wait(self.config.snapshot_timeout).await?;
 
-        let height = loader.load_into(&self.service).await?;
-        let stats = self.service.stats();
+        let mut snapshot = match loader.stream().await {
+            Ok(snapshot) => snapshot,
+            Err(e) => {
+                loader.cleanup().await.ok();
+                return Err(e);
+            }
+        };
+
+        let previous_height = self.service.height();
+        let height = snapshot.height();
+        if height < previous_height {
+            warn!(
+                "snapshot at height {} is behind the books at {}, keeping live books",
+                height, previous_height
+            );
+            loader.cleanup().await.ok();
+            return Ok(None);
+        }
 
+        let mut counts = BlockCounts::default();
+        let pending = self.catch_up(height, &mut counts).await;
+        if self.service.height() < height {
+            let missing = height - self.service.height();
+            warn!("books only reached height {} of snapshot {}", self.service.height(), height);
+            counts.blocks_missing += missing;
+        }
+
+        let divergence = self.repair(&mut snapshot).await;
         loader.cleanup().await.ok();
+        let divergence = divergence?;
 
-        info!(
-            "resync complete: height={}, books={}, orders={}",
-            height, stats.books, stats.total_orders
-        );
+        if let Some(block) = pending {
+            self.apply_block(block, &mut counts);
+        }
 
-        Ok(())
+        Ok(Some(SyncReport {
+            kind: SyncKind::Resync,
+            previous_height,
+            snapshot_height: height,
+            height: self.service.height(),
+            buffered_blocks: 0,
+            counts,
+            divergence: Some(divergence),
+            elapsed: started.elapsed(),
+            completed_at: Utc::now(),
+        }))
+    }
+
+    // Returns the first block past `height`, if one was received while catching up.
+    async fn catch_up(&mut self, height: u64, counts: &mut BlockCounts) -> Option<BookDiffsByBlock> {
+        while self.service.height() < height {
+            match timeout(self.config.snapshot_timeout, self.rx.recv()).await {
+                Ok(Some(block)) if block.block_number > height => return Some(block),
+                Ok(Some(block)) => self.apply_block(block, counts),
+                Ok(None) | Err(_) => break,
+            }
+        }
+        None
     }
-}
\ No newline at end of file
+
+    // Leaves the books at the snapshot height, with only the coins that differed replaced.
+    async fn repair(&self, snapshot: &mut SnapshotStream) -> Result<DivergenceReport> {
+        let mut report = DivergenceReport {
+            height: snapshot.height(),
+            ..Default::default()
+        };
+        let mut seen = HashSet::new();
+
+        while let Some(coin_snap) = snapshot.next_coin().await? {
+            let expected = SnapshotLoader::build_book(&coin_snap, report.height);
+            seen.insert(coin_snap.coin().to_string());
+            report.coins_checked += 1;
+
+            let divergence = match self.service.get(coin_snap.coin()) {
+                Some(live) => compare(&live, &expected),
+                None if expected.total_orders() == 0 => continue,
+                None => compare(&CoinBook::new(coin_snap.coin().to_string()), &expected),
+            };
+
+            if !divergence.is_empty() {
+                self.service.set(expected);
+                report.coins.push(divergence);
+            }
+        }
+
+        // Coins the snapshot no longer lists cannot hold any resting orders.
+        for coin in self.service.coins() {
+            if seen.contains(&coin) {
+                continue;
+            }
+            report.coins_checked += 1;
+            if let Some(live) = self.service.get(&coin)
+                && live.total_orders() > 0
+            {
+                let mut empty = CoinBook::new(coin.clone());
+                empty.set_height(report.height);
+                report.coins.push(compare(&live, &empty));
+                self.service.set(empty);
+            }
+        }
+
+        self.service.set_height(report.height);
+        Ok(report)
+    }
+
+    fn apply_block(&self, block: BookDiffsByBlock, counts: &mut BlockCounts) {
+        let height = self.service.height();
+
+        if block.block_number <= height {
+            counts.blocks_discarded += 1;
+            counts.diffs_discarded += block.len() as u64;
+            return;
+        }
+
+        if block.block_number > height + 1 {
+            let missing = block.block_number - height - 1;
+            warn!("{} blocks missing between height {} and {}", missing, height, block.block_number);
+            counts.blocks_missing += missing;
+        }
+
+        let block_number = block.block_number;
+        let block_time = block.block_time;
+        for diff in block.into_events() {
+            match self.service.apply_diff(diff, block_number, block_time) {
+                ApplyResult::Applied => counts.diffs_applied += 1,
+                ApplyResult::Skipped => counts.diffs_skipped += 1,
+            }
+        }
+
+        counts.blocks_applied += 1;
+        self.service.set_height(block_number);
+    }
+
+    fn finish(&self, report: SyncReport) {
+        if report.is_consistent() {
+            info!("{}", report);
+        } else {
+            warn!("{}", report);
+        }
+        self.service.record_sync(report);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::path::PathBuf;
+
+    use super::*;
+    use crate::orderbook::book::tests::book_with;
+    use crate::parser::schemas::book_diff::{BookDiff, NewOrder, RawBookDiff};
+    use crate::parser::schemas::common::Side;
+    use crate::parser::schemas::{Block, Timestamp};
+
+    fn block(height: u64, events: Vec<BookDiff>) -> BookDiffsByBlock {
+        Block {
+            local_time: Timestamp::from_millis(height as i64),
+            block_time: Timestamp::from_millis(height as i64),
+            block_number: height,
+            events,
+        }
+    }
+
+    fn new_order(oid: u64, px: &str) -> BookDiff {
+        BookDiff {
+            user: format!("0x{:040x}", oid).into(),
+            oid,
+            coin: "BTC".into(),
+            side: Side::Bid,
+            px: px.to_string(),
+            raw_book_diff: RawBookDiff::New { new: NewOrder { sz: "1".to_string() } },
+        }
+    }
+
+    fn removal(oid: u64) -> BookDiff {
+        BookDiff {
+            raw_book_diff: RawBookDiff::Remove("canceled".to_string()),
+            ..new_order(oid, "100")
+        }
+    }
+
+    // Books as loaded from a snapshot at `height`, with one resting bid as oid 1.
+    fn synced_at(height: u64) -> (Sync, mpsc::Sender<BookDiffsByBlock>) {
+        let service = Arc::new(OrderBookService::new());
+        service.set(book_with(&[("100", "1")], &[]));
+        service.set_height(height);
+        let (tx, rx) = mpsc::channel(16);
+        (Sync::new(SyncConfig::default(), service, rx), tx)
+    }
+
+    async fn snapshot_at(height: u64, coins: &[(&str, u64, &str)]) -> (SnapshotStream, PathBuf) {
+        let coins: Vec<_> = coins
+            .iter()
+            .map(|(coin, oid, sz)| {
+                format!(
+                    r#"["{coin}",[[["0x{oid:040x}",{{"coin":"{coin}","side":"B","limitPx":"100","sz":"{sz}","oid":{oid},"timestamp":0,"triggerCondition":"N/A","isTrigger":false,"triggerPx":"0.0","children":[],"isPositionTpsl":false,"reduceOnly":false,"orderType":"Limit","origSz":"{sz}","tif":null,"cloid":null}}]],[]]]"#
+                )
+            })
+            .collect();
+        let path = std::env::temp_dir().join(format!("hl-sync-{}", uuid::Uuid::new_v4()));
+        tokio::fs::write(&path, format!("[{},[{}]]", height, coins.join(","))).await.unwrap();
+        (SnapshotStream::open(&path).await.unwrap(), path)
+    }
+
+    #[tokio::test]
+    async fn repair_replaces_only_diverged_coins() {
+        let (sync, _tx) = synced_at(10);
+        // BTC holds oid 1 at size 1, which the snapshot has at size 2; ETH matches it exactly.
+        let (mut eth, path) = snapshot_at(12, &[("ETH", 7, "3")]).await;
+        sync.service.set(SnapshotLoader::build_book(&eth.next_coin().await.unwrap().unwrap(), 10));
+        std::fs::remove_file(path).unwrap();
+        let btc = sync.service.get("BTC").unwrap();
+        let eth = sync.service.get("ETH").unwrap();
+
+        let (mut snapshot, path) = snapshot_at(12, &[("BTC", 1, "2"), ("ETH", 7, "3")]).await;
+        let report = sync.repair(&mut snapshot).await.unwrap();
+        std::fs::remove_file(path).unwrap();
+
+        assert_eq!(report.height, 12);
+        assert_eq!(report.coins_checked, 2);
+        assert_eq!(report.coins.len(), 1);
+        assert_eq!(report.coins[0].coin, "BTC");
+        assert_eq!(report.coins[0].size_mismatches[0].oid, 1);
+        assert_eq!(sync.service.height(), 12);
+
+        let repaired = sync.service.get("BTC").unwrap();
+        assert!(!Arc::ptr_eq(&repaired, &btc));
+        assert_eq!(repaired.get(1).unwrap().size_str(), "2");
+        assert_eq!(repaired.height(), 12);
+        assert!(Arc::ptr_eq(&sync.service.get("ETH").unwrap(), &eth));
+    }
+
+    #[test]
+    fn blocks_up_to_the_snapshot_are_discarded() {
+        let (sync, _tx) = synced_at(10);
+        let mut counts = BlockCounts::default();
+
+        sync.apply_block(block(9, vec![new_order(20, "99")]), &mut counts);
+        sync.apply_block(block(10, vec![new_order(21, "98"), removal(1)]), &mut counts);
+        sync.apply_block(block(11, vec![new_order(22, "97"), removal(99)]), &mut counts);
+
+        assert_eq!(counts.blocks_discarded, 2);
+        assert_eq!(counts.diffs_discarded, 3);
+        assert_eq!(counts.blocks_applied, 1);
+        assert_eq!(counts.diffs_applied, 1);
+        assert_eq!(counts.diffs_skipped, 1);
+        assert_eq!(counts.blocks_missing, 0);
+        assert_eq!(sync.service.height(), 11);
+
+        let book = sync.service.get("BTC").unwrap();
+        assert!(book.contains(1) && book.contains(22));
+        assert!(!book.contains(20) && !book.contains(21));
+    }
+
+    #[test]
+    fn a_gap_past_the_books_is_counted_as_missing() {
+        let (sync, _tx) = synced_at(10);
+        let mut counts = BlockCounts::default();
+
+        sync.apply_block(block(13, vec![new_order(20, "99")]), &mut counts);
+
+        assert_eq!(counts.blocks_missing, 2);
+        assert_eq!(counts.blocks_applied, 1);
+        assert_eq!(sync.service.height(), 13);
+    }
+
+    #[tokio::test]
+    async fn catch_up_stops_at_the_first_block_past_the_snapshot() {
+        let (mut sync, tx) = synced_at(10);
+        for height in [10, 11, 13] {
+            tx.send(block(height, vec![new_order(height + 100, "99")])).await.unwrap();
+        }
+
+        let mut counts = BlockCounts::default();
+        let pending = sync.catch_up(12, &mut counts).await;
+
+        assert_eq!(pending.map(|b| b.block_number), Some(13));
+        assert_eq!(counts.blocks_discarded, 1);
+        assert_eq!(counts.blocks_applied, 1);
+        assert_eq!(sync.service.height(), 11);
+        assert!(!sync.service.get("BTC").unwrap().contains(113));
+    }
+}
@@ -0,0 +1,239 @@
+// parser/dead_letter.rs
+use std::path::{Path, PathBuf};
+use std::sync::Arc;
+
+use anyhow::{Context, Result};
+use chrono::{DateTime, Utc};
+use dashmap::DashMap;
+use serde::de::DeserializeOwned;
+use sonic_rs::{Deserialize, Serialize};
+use tokio::fs::{self, File, OpenOptions};
+use tokio::io::{AsyncBufReadExt, AsyncWriteExt, BufReader};
+
+use crate::parser::stream::{ParseError, Positioned};
+use crate::reader::Position;
+
+#[derive(Debug, Clone, Deserialize, Serialize)]
+pub struct DeadLetter {
+    pub stream: String,
+    pub line: String,
+    pub position: Position,
+    pub error: String,
+    pub failed_at: DateTime<Utc>,
+}
+
+impl DeadLetter {
+    pub fn from_parse_error(stream: &str, err: &ParseError) -> Self {
+        Self {
+            stream: stream.to_string(),
+            line: err.line.clone(),
+            position: err.position.clone(),
+            error: err.error.to_string(),
+            failed_at: Utc::now(),
+        }
+    }
+}
+
+#[derive(Debug, Clone, Default)]
+pub struct FailureCounters {
+    counts: Arc<DashMap<String, u64>>,
+}
+
+impl FailureCounters {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    pub fn increment(&self, stream: &str) -> u64 {
+        let mut count = self.counts.entry(stream.to_string()).or_insert(0);
+        *count += 1;
+        *count
+    }
+
+    pub fn get(&self, stream: &str) -> u64 {
+        self.counts.get(stream).map(|c| *c).unwrap_or(0)
+    }
+
+    pub fn snapshot(&self) -> Vec<(String, u64)> {
+        let mut counts: Vec<_> = self
+            .counts
+            .iter()
+            .map(|e| (e.key().clone(), *e.value()))
+            .collect();
+        counts.sort();
+        counts
+    }
+}
+
+#[derive(Debug, Clone)]
+pub struct DeadLetterConfig {
+    pub dir: PathBuf,
+    pub max_file_bytes: u64,
+    pub max_files: usize,
+}
+
+impl Default for DeadLetterConfig {
+    fn default() -> Self {
+        Self {
+            dir: PathBuf::from("./dead_letters"),
+            max_file_bytes: 64 * 1024 * 1024,
+            max_files: 8,
+        }
+    }
+}
+
+pub struct DeadLetterSink {
+    stream: String,
+    config: DeadLetterConfig,
+    counters: FailureCounters,
+    file: Option<File>,
+    written: u64,
+}
+
+impl DeadLetterSink {
+    pub async fn open(stream: &str, config: DeadLetterConfig, counters: FailureCounters) -> Result<Self> {
+        fs::create_dir_all(&config.dir)
+            .await
+            .with_context(|| format!("failed to create dead-letter dir {}", config.dir.display()))?;
+
+        Ok(Self {
+            stream: stream.to_string(),
+            config,
+            counters,
+            file: None,
+            written: 0,
+        })
+    }
+
+    pub fn stream(&self) -> &str {
+        &self.stream
+    }
+
+    pub fn failures(&self) -> u64 {
+        self.counters.get(&self.stream)
+    }
+
+    pub fn active_path(&self) -> PathBuf {
+        self.config.dir.join(format!("{}.jsonl", self.stream))
+    }
+
+    pub async fn record(&mut self, err: &ParseError) -> Result<u64> {
+        let count = self.counters.increment(&self.stream);
+        self.write(&DeadLetter::from_parse_error(&self.stream, err)).await?;
+        Ok(count)
+    }
+
+    pub async fn write(&mut self, letter: &DeadLetter) -> Result<()> {
+        let mut bytes = sonic_rs::to_vec(letter)?;
+        bytes.push(b'\n');
+
+        if self.written > 0 && self.written + bytes.len() as u64 > self.config.max_file_bytes {
+            self.rotate().await?;
+        }
+
+        let file = match &mut self.file {
+            Some(file) => file,
+            None => {
+                let path = self.active_path();
+                let file = OpenOptions::new()
+                    .create(true)
+                    .append(true)
+                    .open(&path)
+                    .await
+                    .with_context(|| format!("failed to open dead-letter file {}", path.display()))?;
+                self.written = file.metadata().await?.len();
+                self.file.insert(file)
+            }
+        };
+
+        file.write_all(&bytes).await?;
+        file.flush().await?;
+        self.written += bytes.len() as u64;
+
+        Ok(())
+    }
+
+    async fn rotate(&mut self) -> Result<()> {
+        if let Some(mut file) = self.file.take() {
+            file.flush().await?;
+        }
+
+        let active = self.active_path();
+        let rotated = self.config.dir.join(format!(
+            "{}.{}.jsonl",
+            self.stream,
+            Utc::now().format("%Y%m%dT%H%M%S%.3f")
+        ));
+        fs::rename(&active, &rotated)
+            .await
+            .with_context(|| format!("failed to rotate dead-letter file {}", active.display()))?;
+        self.written = 0;
+
+        self.prune().await
+    }
+
+    async fn prune(&self) -> Result<()> {
+        let prefix = format!("{}.", self.stream);
+        let mut rotated = Vec::new();
+
+        let mut entries = fs::read_dir(&self.config.dir).await?;
+        while let Some(entry) = entries.next_entry().await? {
+            let name = entry.file_name();
+            let Some(name) = name.to_str() else {
+                continue;
+            };
+            let is_rotated = name
+                .strip_prefix(&prefix)
+                .and_then(|rest| rest.strip_suffix(".jsonl"))
+                .is_some_and(|stamp| stamp.starts_with(|c: char| c.is_ascii_digit()));
+            if is_rotated {
+                rotated.push(entry.path());
+            }
+        }
+
+        rotated.sort();
+        let excess = rotated.len().saturating_sub(self.config.max_files);
+        for path in &rotated[..excess] {
+            fs::remove_file(path).await?;
+        }
+
+        Ok(())
+    }
+}
+
+pub struct Replay<T> {
+    pub recovered: Vec<Positioned<T>>,
+    pub failed: Vec<DeadLetter>,
+}
+
+pub async fn replay<T: DeserializeOwned>(path: &Path) -> Result<Replay<T>> {
+    let file = File::open(path)
+        .await
+        .with_context(|| format!("failed to open dead-letter file {}", path.display()))?;
+    let mut lines = BufReader::new(file).lines();
+
+    let mut recovered = Vec::new();
+    let mut failed = Vec::new();
+
+    while let Some(raw) = lines.next_line().await? {
+        if raw.trim().is_empty() {
+            continue;
+        }
+
+        let mut letter: DeadLetter = sonic_rs::from_str(&raw)
+            .with_context(|| format!("malformed dead-letter entry in {}", path.display()))?;
+
+        match sonic_rs::from_str(&letter.line) {
+            Ok(value) => recovered.push(Positioned {
+                position: letter.position,
+                value,
+            }),
+            Err(e) => {
+                letter.error = e.to_string();
+                failed.push(letter);
+            }
+        }
+    }
+
+    Ok(Replay { recovered, failed })
+}
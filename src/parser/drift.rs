@@ -0,0 +1,287 @@
+// parser/drift.rs
+use std::collections::BTreeMap;
+use std::fmt;
+use std::marker::PhantomData;
+
+use serde::de::DeserializeOwned;
+use sonic_rs::{Deserialize, JsonContainerTrait, JsonValueTrait, Serialize, Value};
+
+use crate::parser::schemas::system_action::Action;
+use crate::parser::schemas::{Block, BookDiff, Fill, MiscEvent, OrderStatus, SystemAction, Trade, TwapStatus};
+use crate::reader::{FileEvent, Position};
+
+const MAX_SAMPLES: usize = 3;
+
+pub trait DriftCheck {
+    fn unknown_tags(&self) -> Vec<(String, String)> {
+        Vec::new()
+    }
+}
+
+impl DriftCheck for Trade {}
+impl DriftCheck for Fill {}
+impl DriftCheck for OrderStatus {}
+impl DriftCheck for BookDiff {}
+impl DriftCheck for TwapStatus {}
+impl DriftCheck for MiscEvent {}
+
+impl<T: DriftCheck> DriftCheck for Block<T> {
+    fn unknown_tags(&self) -> Vec<(String, String)> {
+        self.events
+            .iter()
+            .flat_map(|event| event.unknown_tags())
+            .map(|(path, tag)| (format!(".events[]{}", path), tag))
+            .collect()
+    }
+}
+
+impl DriftCheck for SystemAction {
+    fn unknown_tags(&self) -> Vec<(String, String)> {
+        match &self.action {
+            Action::Unknown(_) => vec![(".action.type".to_string(), self.action.type_name().to_string())],
+            Action::Known { .. } => Vec::new(),
+        }
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Deserialize, Serialize)]
+#[serde(rename_all = "snake_case")]
+pub enum DriftKind {
+    InvalidJson,
+    UnknownField,
+    UnknownTag,
+    TypeMismatch,
+    LossyRoundTrip,
+}
+
+#[derive(Debug, Clone, Deserialize, Serialize)]
+pub struct DriftSample {
+    pub position: Position,
+    pub line: String,
+}
+
+#[derive(Debug, Clone, Deserialize, Serialize)]
+pub struct DriftIssue {
+    pub kind: DriftKind,
+    pub path: String,
+    pub detail: String,
+    pub count: u64,
+    pub samples: Vec<DriftSample>,
+}
+
+#[derive(Debug, Clone, Default, Deserialize, Serialize)]
+pub struct StreamDrift {
+    pub lines: u64,
+    pub clean: u64,
+    pub issues: Vec<DriftIssue>,
+}
+
+impl StreamDrift {
+    pub fn has_drift(&self) -> bool {
+        !self.issues.is_empty()
+    }
+}
+
+#[derive(Debug, Clone, Default, Deserialize, Serialize)]
+pub struct DriftReport {
+    pub streams: BTreeMap<String, StreamDrift>,
+}
+
+impl DriftReport {
+    pub fn has_drift(&self) -> bool {
+        self.streams.values().any(StreamDrift::has_drift)
+    }
+}
+
+impl fmt::Display for DriftReport {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        for (stream, drift) in &self.streams {
+            writeln!(f, "{}: {} lines, {} clean, {} issues", stream, drift.lines, drift.clean, drift.issues.len())?;
+            for issue in &drift.issues {
+                writeln!(f, "  {:?} {} {} (x{})", issue.kind, issue.path, issue.detail, issue.count)?;
+                for sample in &issue.samples {
+                    writeln!(f, "    at {}: {}", sample.position, sample.line)?;
+                }
+            }
+        }
+        Ok(())
+    }
+}
+
+pub struct DriftDetector<T> {
+    lines: u64,
+    clean: u64,
+    issues: BTreeMap<(DriftKind, String, String), DriftIssue>,
+    _marker: PhantomData<T>,
+}
+
+impl<T> Default for DriftDetector<T> {
+    fn default() -> Self {
+        Self {
+            lines: 0,
+            clean: 0,
+            issues: BTreeMap::new(),
+            _marker: PhantomData,
+        }
+    }
+}
+
+impl<T: DeserializeOwned + serde::Serialize + DriftCheck> DriftDetector<T> {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    pub fn check_event(&mut self, event: &FileEvent) -> bool {
+        self.check(&event.line, &event.position)
+    }
+
+    pub fn check(&mut self, line: &str, position: &Position) -> bool {
+        self.lines += 1;
+
+        let raw: Value = match sonic_rs::from_str(line) {
+            Ok(v) => v,
+            Err(e) => {
+                self.record(DriftKind::InvalidJson, "", &strip_location(&e.to_string()), line, position);
+                return false;
+            }
+        };
+
+        let typed: T = match sonic_rs::from_str(line) {
+            Ok(t) => t,
+            Err(e) => {
+                let message = strip_location(&e.to_string());
+                let kind = if message.starts_with("unknown variant") {
+                    DriftKind::UnknownTag
+                } else {
+                    DriftKind::TypeMismatch
+                };
+                self.record(kind, "", &message, line, position);
+                return false;
+            }
+        };
+
+        let mut found = Vec::new();
+        for (path, tag) in typed.unknown_tags() {
+            found.push((DriftKind::UnknownTag, path, tag));
+        }
+
+        match sonic_rs::to_value(&typed) {
+            Ok(round_trip) => diff(&raw, &round_trip, &mut String::new(), &mut found),
+            Err(e) => found.push((DriftKind::LossyRoundTrip, String::new(), e.to_string())),
+        }
+
+        if found.is_empty() {
+            self.clean += 1;
+            return true;
+        }
+
+        found.sort();
+        found.dedup();
+        for (kind, path, detail) in found {
+            self.record(kind, &path, &detail, line, position);
+        }
+        false
+    }
+
+    pub fn finish(self) -> StreamDrift {
+        StreamDrift {
+            lines: self.lines,
+            clean: self.clean,
+            issues: self.issues.into_values().collect(),
+        }
+    }
+
+    fn record(&mut self, kind: DriftKind, path: &str, detail: &str, line: &str, position: &Position) {
+        let issue = self
+            .issues
+            .entry((kind, path.to_string(), detail.to_string()))
+            .or_insert_with(|| DriftIssue {
+                kind,
+                path: path.to_string(),
+                detail: detail.to_string(),
+                count: 0,
+                samples: Vec::new(),
+            });
+
+        issue.count += 1;
+        if issue.samples.len() < MAX_SAMPLES {
+            issue.samples.push(DriftSample {
+                position: position.clone(),
+                line: line.to_string(),
+            });
+        }
+    }
+}
+
+fn diff(raw: &Value, typed: &Value, path: &mut String, found: &mut Vec<(DriftKind, String, String)>) {
+    if let (Some(raw_obj), Some(typed_obj)) = (raw.as_object(), typed.as_object()) {
+        // An unrecognized tag makes every sibling field look unknown; report only the tag.
+        if let (Some(raw_tag), Some(typed_tag)) = (raw_obj.get(&"type"), typed_obj.get(&"type"))
+            && raw_tag != typed_tag
+            && let Some(tag) = raw_tag.as_str()
+        {
+            found.push((DriftKind::UnknownTag, format!("{}.type", path), tag.to_string()));
+            return;
+        }
+
+        for (key, raw_child) in raw_obj.iter() {
+            let len = path.len();
+            path.push('.');
+            path.push_str(key);
+            match typed_obj.get(&key) {
+                Some(typed_child) => diff(raw_child, typed_child, path, found),
+                None => found.push((DriftKind::UnknownField, path.clone(), describe(raw_child))),
+            }
+            path.truncate(len);
+        }
+        return;
+    }
+
+    if let (Some(raw_arr), Some(typed_arr)) = (raw.as_array(), typed.as_array()) {
+        let len = path.len();
+        path.push_str("[]");
+        for (i, raw_child) in raw_arr.iter().enumerate() {
+            match typed_arr.get(i) {
+                Some(typed_child) => diff(raw_child, typed_child, path, found),
+                None => found.push((DriftKind::UnknownField, path.clone(), describe(raw_child))),
+            }
+        }
+        path.truncate(len);
+        return;
+    }
+
+    if raw == typed {
+        return;
+    }
+
+    if raw.get_type() != typed.get_type() {
+        found.push((DriftKind::TypeMismatch, path.clone(), format!("{} read as {}", describe(raw), describe(typed))));
+        return;
+    }
+
+    found.push((DriftKind::LossyRoundTrip, path.clone(), describe(raw)));
+}
+
+fn describe(value: &Value) -> String {
+    if value.is_str() {
+        "string".to_string()
+    } else if value.is_number() {
+        "number".to_string()
+    } else if value.is_boolean() {
+        "bool".to_string()
+    } else if value.is_null() {
+        "null".to_string()
+    } else if value.is_array() {
+        "array".to_string()
+    } else {
+        "object".to_string()
+    }
+}
+
+fn strip_location(message: &str) -> String {
+    let first = message.lines().next().unwrap_or(message);
+    match first.find(" at line ") {
+        Some(i) => first[..i].to_string(),
+        None => first.to_string(),
+    }
+}
@@ -0,0 +1,66 @@
+// parser/intern.rs
+use std::collections::HashSet;
+use std::sync::Arc;
+
+use crate::parser::schemas::BorrowedSchema;
+
+pub type Symbol = Arc<str>;
+
+#[derive(Debug, Default)]
+pub struct Interner {
+    symbols: HashSet<Symbol>,
+}
+
+impl Interner {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    pub fn intern(&mut self, s: &str) -> Symbol {
+        if let Some(symbol) = self.symbols.get(s) {
+            return symbol.clone();
+        }
+
+        let symbol: Symbol = Arc::from(s);
+        self.symbols.insert(symbol.clone());
+        symbol
+    }
+
+    pub fn get(&self, s: &str) -> Option<Symbol> {
+        self.symbols.get(s).cloned()
+    }
+
+    pub fn len(&self) -> usize {
+        self.symbols.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.symbols.is_empty()
+    }
+}
+
+#[derive(Debug, Default)]
+pub struct Symbols {
+    pub coins: Interner,
+    pub addresses: Interner,
+}
+
+impl Symbols {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    pub fn coin(&mut self, coin: &str) -> Symbol {
+        self.coins.intern(coin)
+    }
+
+    pub fn address(&mut self, address: &str) -> Symbol {
+        self.addresses.intern(address)
+    }
+}
+
+// Builds the owned record from its borrowed parse, sharing coin and address strings through
+// `symbols` instead of allocating them per record.
+pub trait Intern: BorrowedSchema + Sized {
+    fn intern(borrowed: Self::Ref<'_>, symbols: &mut Symbols) -> Self;
+}
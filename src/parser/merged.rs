@@ -0,0 +1,396 @@
+// parser/merged.rs
+use std::cmp::Ordering;
+use std::collections::BinaryHeap;
+use std::fmt;
+
+use anyhow::Result;
+use tokio::sync::mpsc;
+use tokio::task::JoinHandle;
+use tokio::time::{Duration, timeout};
+
+use crate::parser::schemas::{HasTimestamp, Timestamp};
+use crate::parser::stream::PositionedSource;
+use crate::reader::{EndOfStream, Position};
+
+#[derive(Debug, Clone)]
+pub struct MergeConfig {
+    pub lateness: Duration,
+    pub max_buffered: usize,
+    pub channel_capacity: usize,
+}
+
+impl Default for MergeConfig {
+    fn default() -> Self {
+        Self {
+            lateness: Duration::from_millis(500),
+            max_buffered: 100_000,
+            channel_capacity: 10_000,
+        }
+    }
+}
+
+#[derive(Debug, Clone)]
+pub struct Merged<E> {
+    pub stream: &'static str,
+    pub time: Timestamp,
+    pub position: Position,
+    pub value: E,
+}
+
+#[derive(Debug)]
+pub struct StreamFailure {
+    pub stream: &'static str,
+    pub error: anyhow::Error,
+}
+
+impl fmt::Display for StreamFailure {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{} reader error: {}", self.stream, self.error)
+    }
+}
+
+impl std::error::Error for StreamFailure {}
+
+struct Record<E> {
+    stream: usize,
+    time: Option<Timestamp>,
+    position: Position,
+    value: E,
+}
+
+enum Message<E> {
+    Record(Record<E>),
+    Failed(usize, anyhow::Error),
+    Closed(usize),
+}
+
+struct Entry<E> {
+    time: Timestamp,
+    stream: usize,
+    seq: u64,
+    merged: Merged<E>,
+}
+
+impl<E> Entry<E> {
+    fn key(&self) -> (Timestamp, usize, u64) {
+        (self.time, self.stream, self.seq)
+    }
+}
+
+impl<E> PartialEq for Entry<E> {
+    fn eq(&self, other: &Self) -> bool {
+        self.key() == other.key()
+    }
+}
+
+impl<E> Eq for Entry<E> {}
+
+impl<E> PartialOrd for Entry<E> {
+    fn partial_cmp(&self, other: &Self) -> Option<Ordering> {
+        Some(self.cmp(other))
+    }
+}
+
+impl<E> Ord for Entry<E> {
+    fn cmp(&self, other: &Self) -> Ordering {
+        other.key().cmp(&self.key())
+    }
+}
+
+pub struct MergedReader<E> {
+    config: MergeConfig,
+    tx: mpsc::Sender<Message<E>>,
+    rx: mpsc::Receiver<Message<E>>,
+    streams: Vec<&'static str>,
+    stream_times: Vec<Timestamp>,
+    tasks: Vec<JoinHandle<()>>,
+    heap: BinaryHeap<Entry<E>>,
+    open: usize,
+    seq: u64,
+    max_time: Timestamp,
+    last_emitted: Timestamp,
+    late: u64,
+}
+
+impl<E: Send + 'static> MergedReader<E> {
+    pub fn new(config: MergeConfig) -> Self {
+        let (tx, rx) = mpsc::channel(config.channel_capacity);
+        Self {
+            config,
+            tx,
+            rx,
+            streams: Vec::new(),
+            stream_times: Vec::new(),
+            tasks: Vec::new(),
+            heap: BinaryHeap::new(),
+            open: 0,
+            seq: 0,
+            max_time: Timestamp::MIN,
+            last_emitted: Timestamp::MIN,
+            late: 0,
+        }
+    }
+
+    // Streams added earlier win ties within the same block time, so add them in causal order
+    // (e.g. order statuses before book diffs before trades and fills).
+    pub fn add<T, S>(&mut self, name: &'static str, mut reader: S, wrap: fn(T) -> E)
+    where
+        T: HasTimestamp + Send + 'static,
+        S: PositionedSource<T>,
+    {
+        let index = self.streams.len();
+        let tx = self.tx.clone();
+
+        self.streams.push(name);
+        self.stream_times.push(Timestamp::MIN);
+        self.open += 1;
+
+        self.tasks.push(tokio::spawn(async move {
+            loop {
+                let message = match reader.next_positioned().await {
+                    Ok(p) => Message::Record(Record {
+                        stream: index,
+                        time: p.value.timestamp(),
+                        position: p.position,
+                        value: wrap(p.value),
+                    }),
+                    Err(e) if e.is::<EndOfStream>() => {
+                        let _ = tx.send(Message::Closed(index)).await;
+                        return;
+                    }
+                    Err(e) => Message::Failed(index, e),
+                };
+
+                if tx.send(message).await.is_err() {
+                    return;
+                }
+            }
+        }));
+    }
+
+    pub fn streams(&self) -> &[&'static str] {
+        &self.streams
+    }
+
+    pub fn buffered(&self) -> usize {
+        self.heap.len()
+    }
+
+    pub fn late_records(&self) -> u64 {
+        self.late
+    }
+
+    pub async fn next(&mut self) -> Result<Merged<E>> {
+        loop {
+            if self.ready() {
+                return Ok(self.emit());
+            }
+
+            if self.open == 0 {
+                return Err(EndOfStream.into());
+            }
+
+            let message = if self.heap.is_empty() {
+                self.rx.recv().await
+            } else {
+                match timeout(self.config.lateness, self.rx.recv()).await {
+                    Ok(message) => message,
+                    Err(_) => return Ok(self.emit()),
+                }
+            };
+
+            match message {
+                Some(Message::Record(record)) => self.push(record),
+                Some(Message::Failed(index, error)) => {
+                    return Err(StreamFailure {
+                        stream: self.streams[index],
+                        error,
+                    }
+                    .into());
+                }
+                Some(Message::Closed(index)) => {
+                    tracing::debug!("{} reached end of stream", self.streams[index]);
+                    self.open -= 1;
+                }
+                None => self.open = 0,
+            }
+        }
+    }
+
+    fn ready(&self) -> bool {
+        let Some(head) = self.heap.peek() else {
+            return false;
+        };
+
+        self.open == 0
+            || self.heap.len() >= self.config.max_buffered
+            || head.time <= self.max_time.saturating_sub(self.config.lateness)
+    }
+
+    fn push(&mut self, record: Record<E>) {
+        // Records without their own block time (raw book diffs) ride along at the newest time seen
+        // so far; per-stream times are clamped so a stream is never reordered against itself.
+        let time = record
+            .time
+            .unwrap_or(self.max_time)
+            .max(self.stream_times[record.stream]);
+
+        self.stream_times[record.stream] = time;
+        self.max_time = self.max_time.max(time);
+        self.seq += 1;
+
+        // Fills only carry milliseconds while statuses, diffs and trades carry the block time in
+        // nanoseconds, so records are ordered at millisecond precision: a fill then ties with the
+        // rest of its block and falls back on the order streams were added in.
+        self.heap.push(Entry {
+            time: Timestamp::from_millis(time.as_millis()),
+            stream: record.stream,
+            seq: self.seq,
+            merged: Merged {
+                stream: self.streams[record.stream],
+                time,
+                position: record.position,
+                value: record.value,
+            },
+        });
+    }
+
+    fn emit(&mut self) -> Merged<E> {
+        let entry = self.heap.pop().expect("emit called on empty merge buffer");
+
+        if entry.time < self.last_emitted {
+            self.late += 1;
+            tracing::debug!(
+                "{} record at {} arrived after {} was emitted",
+                entry.merged.stream,
+                entry.time,
+                self.last_emitted
+            );
+        }
+        self.last_emitted = self.last_emitted.max(entry.time);
+
+        entry.merged
+    }
+}
+
+impl<E> Drop for MergedReader<E> {
+    fn drop(&mut self) {
+        for task in &self.tasks {
+            task.abort();
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::collections::VecDeque;
+    use std::path::Path;
+    use std::sync::Arc;
+
+    use super::*;
+    use crate::parser::stream::Positioned;
+
+    struct Fake {
+        time: Timestamp,
+        id: &'static str,
+    }
+
+    impl HasTimestamp for Fake {
+        fn timestamp(&self) -> Option<Timestamp> {
+            Some(self.time)
+        }
+    }
+
+    struct FakeSource(VecDeque<Fake>);
+
+    impl FakeSource {
+        fn new(records: &[(i64, &'static str)]) -> Self {
+            Self::at(records, Timestamp::from_millis)
+        }
+
+        fn nanos(records: &[(i64, &'static str)]) -> Self {
+            Self::at(records, Timestamp::from_nanos)
+        }
+
+        fn at(records: &[(i64, &'static str)], time: fn(i64) -> Timestamp) -> Self {
+            Self(records.iter().map(|&(t, id)| Fake { time: time(t), id }).collect())
+        }
+    }
+
+    impl PositionedSource<Fake> for FakeSource {
+        async fn next_positioned(&mut self) -> Result<Positioned<Fake>> {
+            let value = self.0.pop_front().ok_or(EndOfStream)?;
+            Ok(Positioned {
+                position: Position {
+                    path: Arc::from(Path::new(value.id)),
+                    hour: None,
+                    offset: 0,
+                    next_offset: 0,
+                    line: None,
+                    read_at: chrono::Utc::now(),
+                    file_id: None,
+                },
+                value,
+            })
+        }
+    }
+
+    async fn drain(mut merged: MergedReader<&'static str>) -> Vec<(&'static str, i64)> {
+        let mut out = Vec::new();
+        loop {
+            match merged.next().await {
+                Ok(m) => out.push((m.value, m.time.as_millis())),
+                Err(e) if e.is::<EndOfStream>() => return out,
+                Err(e) => panic!("{}", e),
+            }
+        }
+    }
+
+    #[tokio::test]
+    async fn merges_by_time_then_add_order() {
+        let mut merged = MergedReader::new(MergeConfig::default());
+        merged.add("a", FakeSource::new(&[(100, "a1"), (300, "a2"), (300, "a3")]), |f: Fake| f.id);
+        merged.add("b", FakeSource::new(&[(100, "b1"), (200, "b2"), (300, "b3")]), |f: Fake| f.id);
+        merged.add("c", FakeSource::new(&[(50, "c1"), (300, "c2")]), |f: Fake| f.id);
+
+        assert_eq!(
+            drain(merged).await,
+            vec![
+                ("c1", 50),
+                ("a1", 100),
+                ("b1", 100),
+                ("b2", 200),
+                ("a2", 300),
+                ("a3", 300),
+                ("b3", 300),
+                ("c2", 300),
+            ]
+        );
+    }
+
+    #[tokio::test]
+    async fn never_reorders_a_stream_against_itself() {
+        let mut merged = MergedReader::new(MergeConfig::default());
+        merged.add("a", FakeSource::new(&[(100, "a1"), (250, "a2")]), |f: Fake| f.id);
+        merged.add("b", FakeSource::new(&[(200, "b1"), (150, "b2"), (300, "b3")]), |f: Fake| f.id);
+
+        // b2 claims an earlier time than b1, so it is held at b1's time rather than jumping ahead.
+        assert_eq!(
+            drain(merged).await,
+            vec![("a1", 100), ("b1", 200), ("b2", 200), ("a2", 250), ("b3", 300)]
+        );
+    }
+
+    #[tokio::test]
+    async fn millisecond_fills_stay_behind_their_own_block() {
+        const MS: i64 = 1_000_000;
+        let mut merged = MergedReader::new(MergeConfig::default());
+        // Two blocks at 1000.4ms and 1070.9ms, as the node stamps statuses and diffs.
+        merged.add("statuses", FakeSource::nanos(&[(1000 * MS + 400_123, "s1"), (1070 * MS + 912_004, "s2")]), |f: Fake| f.id);
+        merged.add("diffs", FakeSource::nanos(&[(1000 * MS + 400_123, "d1"), (1070 * MS + 912_004, "d2")]), |f: Fake| f.id);
+        merged.add("fills", FakeSource::new(&[(1000, "f1"), (1070, "f2")]), |f: Fake| f.id);
+
+        let order: Vec<_> = drain(merged).await.into_iter().map(|(id, _)| id).collect();
+        assert_eq!(order, ["s1", "d1", "f1", "s2", "d2", "f2"]);
+    }
+}
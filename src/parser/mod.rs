@@ -1,4 +1,14 @@
+pub mod dead_letter;
+pub mod drift;
+pub mod intern;
+pub mod merged;
+pub mod pipeline;
 pub mod schemas;
 pub mod stream;
 
-pub use stream::StreamReader;
\ No newline at end of file
+pub use dead_letter::{DeadLetter, DeadLetterConfig, DeadLetterSink, FailureCounters, Replay, replay};
+pub use drift::{DriftCheck, DriftDetector, DriftKind, DriftReport, StreamDrift};
+pub use intern::{Intern, Interner, Symbol, Symbols};
+pub use merged::{MergeConfig, Merged, MergedReader, StreamFailure};
+pub use pipeline::{ParsePipeline, PipelineConfig, PipelineHandle, PipelineStats};
+pub use stream::{BlockStreamReader, ParseError, Positioned, PositionedSource, StreamReader};
@@ -0,0 +1,328 @@
+// parser/pipeline.rs
+use std::collections::VecDeque;
+use std::fmt;
+use std::sync::atomic::{AtomicU64, Ordering};
+use std::sync::{Arc, Mutex, PoisonError};
+
+use anyhow::{Result, anyhow};
+use serde::de::DeserializeOwned;
+use tokio::sync::mpsc;
+use tokio::task::JoinHandle;
+use tokio::time::{Duration, Instant};
+
+use crate::parser::intern::{Intern, Symbols};
+use crate::parser::stream::{ParseError, Positioned, PositionedSource, parse_event};
+use crate::reader::{Checkpoint, EndOfStream, FileEvent, Reader};
+
+#[derive(Debug, Clone)]
+pub struct PipelineConfig {
+    pub chunk_size: usize,
+    pub max_in_flight: usize,
+}
+
+impl Default for PipelineConfig {
+    fn default() -> Self {
+        let workers = std::thread::available_parallelism().map_or(4, |n| n.get());
+        Self {
+            chunk_size: 512,
+            max_in_flight: workers * 2,
+        }
+    }
+}
+
+#[derive(Debug, Default)]
+struct PipelineMetrics {
+    lines: AtomicU64,
+    bytes: AtomicU64,
+    parsed: AtomicU64,
+    errors: AtomicU64,
+    chunks: AtomicU64,
+    in_flight: AtomicU64,
+    parse_nanos: AtomicU64,
+}
+
+#[derive(Debug, Clone, Copy)]
+pub struct PipelineStats {
+    pub lines: u64,
+    pub bytes: u64,
+    pub parsed: u64,
+    pub errors: u64,
+    pub chunks: u64,
+    pub in_flight: u64,
+    pub parse_time: Duration,
+    pub elapsed: Duration,
+}
+
+impl PipelineStats {
+    pub fn lines_per_sec(&self) -> f64 {
+        self.lines as f64 / self.elapsed.as_secs_f64().max(f64::EPSILON)
+    }
+
+    pub fn mb_per_sec(&self) -> f64 {
+        self.bytes as f64 / 1_000_000.0 / self.elapsed.as_secs_f64().max(f64::EPSILON)
+    }
+}
+
+impl fmt::Display for PipelineStats {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(
+            f,
+            "{} lines ({:.0}/s, {:.1} MB/s), {} parse errors, {} chunks in flight, {:?} parsing",
+            self.lines,
+            self.lines_per_sec(),
+            self.mb_per_sec(),
+            self.errors,
+            self.in_flight,
+            self.parse_time
+        )
+    }
+}
+
+pub struct PipelineHandle {
+    metrics: Arc<PipelineMetrics>,
+    started: Instant,
+}
+
+impl PipelineHandle {
+    pub fn stats(&self) -> PipelineStats {
+        let m = &self.metrics;
+        PipelineStats {
+            lines: m.lines.load(Ordering::Relaxed),
+            bytes: m.bytes.load(Ordering::Relaxed),
+            parsed: m.parsed.load(Ordering::Relaxed),
+            errors: m.errors.load(Ordering::Relaxed),
+            chunks: m.chunks.load(Ordering::Relaxed),
+            in_flight: m.in_flight.load(Ordering::Relaxed),
+            parse_time: Duration::from_nanos(m.parse_nanos.load(Ordering::Relaxed)),
+            elapsed: self.started.elapsed(),
+        }
+    }
+}
+
+enum Job<T> {
+    Parsed(JoinHandle<Vec<Result<Positioned<T>>>>),
+    Failed(anyhow::Error),
+}
+
+pub struct ParsePipeline<T> {
+    rx: mpsc::Receiver<Job<T>>,
+    ready: VecDeque<Result<Positioned<T>>>,
+    producer: JoinHandle<()>,
+    metrics: Arc<PipelineMetrics>,
+    started: Instant,
+    last: Option<Checkpoint>,
+}
+
+impl<T: DeserializeOwned + Send + 'static> ParsePipeline<T> {
+    pub fn spawn(reader: Reader, config: PipelineConfig) -> Self {
+        Self::spawn_with(reader, config, |chunk| chunk.into_iter().map(parse_event).collect())
+    }
+
+    // Workers parse the borrowed view of each line and build the owned record from it, so coins
+    // and addresses are shared across the whole stream instead of allocated per record.
+    pub fn spawn_interned(reader: Reader, config: PipelineConfig) -> Self
+    where
+        T: Intern,
+    {
+        let symbols = Arc::new(Mutex::new(Symbols::new()));
+        Self::spawn_with(reader, config, move |chunk| parse_interned(chunk, &symbols))
+    }
+
+    fn spawn_with<F>(mut reader: Reader, config: PipelineConfig, parse: F) -> Self
+    where
+        F: Fn(Vec<FileEvent>) -> Vec<Result<Positioned<T>>> + Send + Sync + 'static,
+    {
+        let parse = Arc::new(parse);
+        let (tx, rx) = mpsc::channel(config.max_in_flight.max(1));
+        let metrics = Arc::new(PipelineMetrics::default());
+        let chunk_size = config.chunk_size.max(1);
+
+        let producer_metrics = metrics.clone();
+        let producer = tokio::spawn(async move {
+            loop {
+                let batch = match reader.next_batch().await {
+                    Ok(batch) => batch,
+                    Err(e) => {
+                        let finished = e.is::<EndOfStream>();
+                        if tx.send(Job::Failed(e)).await.is_err() || finished {
+                            return;
+                        }
+                        continue;
+                    }
+                };
+
+                let mut batch = batch.into_iter();
+                loop {
+                    let chunk: Vec<FileEvent> = batch.by_ref().take(chunk_size).collect();
+                    if chunk.is_empty() {
+                        break;
+                    }
+
+                    let metrics = producer_metrics.clone();
+                    let parse = parse.clone();
+                    metrics.in_flight.fetch_add(1, Ordering::Relaxed);
+                    let handle = tokio::task::spawn_blocking(move || parse_chunk(chunk, &*parse, &metrics));
+
+                    if tx.send(Job::Parsed(handle)).await.is_err() {
+                        return;
+                    }
+                }
+            }
+        });
+
+        Self {
+            rx,
+            ready: VecDeque::new(),
+            producer,
+            metrics,
+            started: Instant::now(),
+            last: None,
+        }
+    }
+
+    pub fn handle(&self) -> PipelineHandle {
+        PipelineHandle {
+            metrics: self.metrics.clone(),
+            started: self.started,
+        }
+    }
+
+    pub fn checkpoint(&self) -> Option<Checkpoint> {
+        self.last.clone()
+    }
+
+    pub async fn next(&mut self) -> Result<T> {
+        self.next_positioned().await.map(|p| p.value)
+    }
+
+    pub async fn next_positioned(&mut self) -> Result<Positioned<T>> {
+        loop {
+            if let Some(result) = self.ready.pop_front() {
+                if let Ok(record) = &result {
+                    self.last = Some(record.checkpoint());
+                }
+                return result;
+            }
+
+            match self.rx.recv().await {
+                Some(Job::Parsed(handle)) => {
+                    let parsed = handle.await.map_err(|e| anyhow!("parse worker failed: {}", e))?;
+                    self.ready.extend(parsed);
+                }
+                Some(Job::Failed(e)) => return Err(e),
+                None => return Err(EndOfStream.into()),
+            }
+        }
+    }
+}
+
+impl<T: DeserializeOwned + Send + 'static> PositionedSource<T> for ParsePipeline<T> {
+    async fn next_positioned(&mut self) -> Result<Positioned<T>> {
+        ParsePipeline::next_positioned(self).await
+    }
+}
+
+impl<T> Drop for ParsePipeline<T> {
+    fn drop(&mut self) {
+        self.producer.abort();
+    }
+}
+
+fn parse_chunk<T>(
+    chunk: Vec<FileEvent>,
+    parse: &dyn Fn(Vec<FileEvent>) -> Vec<Result<Positioned<T>>>,
+    metrics: &PipelineMetrics,
+) -> Vec<Result<Positioned<T>>> {
+    let started = Instant::now();
+    let lines = chunk.len() as u64;
+    let bytes: u64 = chunk.iter().map(|e| e.line.len() as u64).sum();
+
+    let parsed = parse(chunk);
+    let errors = parsed.iter().filter(|r| r.is_err()).count() as u64;
+
+    metrics.lines.fetch_add(lines, Ordering::Relaxed);
+    metrics.bytes.fetch_add(bytes, Ordering::Relaxed);
+    metrics.parsed.fetch_add(lines - errors, Ordering::Relaxed);
+    metrics.errors.fetch_add(errors, Ordering::Relaxed);
+    metrics.chunks.fetch_add(1, Ordering::Relaxed);
+    metrics.in_flight.fetch_sub(1, Ordering::Relaxed);
+    metrics
+        .parse_nanos
+        .fetch_add(started.elapsed().as_nanos() as u64, Ordering::Relaxed);
+
+    parsed
+}
+
+// Parsing happens outside the lock; it is only held to look the chunk's names up.
+fn parse_interned<T: Intern>(chunk: Vec<FileEvent>, symbols: &Mutex<Symbols>) -> Vec<Result<Positioned<T>>> {
+    let values: Vec<Result<T, sonic_rs::Error>> = {
+        let borrowed: Vec<Result<T::Ref<'_>, sonic_rs::Error>> =
+            chunk.iter().map(|event| sonic_rs::from_str(&event.line)).collect();
+        let mut symbols = symbols.lock().unwrap_or_else(PoisonError::into_inner);
+        borrowed.into_iter().map(|r| r.map(|value| T::intern(value, &mut symbols))).collect()
+    };
+
+    chunk
+        .into_iter()
+        .zip(values)
+        .map(|(event, value)| match value {
+            Ok(value) => Ok(Positioned {
+                position: event.position,
+                value,
+            }),
+            Err(error) => Err(ParseError {
+                position: event.position,
+                line: event.line,
+                error,
+            }
+            .into()),
+        })
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::parser::schemas::BookDiffsByBlock;
+
+    #[tokio::test]
+    async fn interned_blocks_share_names_and_keep_parse_errors() {
+        let base = std::env::temp_dir().join(format!("hl-pipeline-{}", uuid::Uuid::new_v4()));
+        std::fs::create_dir_all(base.join("hourly/20250101")).unwrap();
+        let diff = |oid| {
+            format!(
+                r#"{{"user":"0xabc","oid":{},"coin":"BTC","side":"B","px":"100","raw_book_diff":{{"new":{{"sz":"1"}}}}}}"#,
+                oid
+            )
+        };
+        let block = |height, events: &[String]| {
+            format!(
+                r#"{{"local_time":"2025-01-01T00:00:00.1","block_time":"2025-01-01T00:00:00.1","block_number":{},"events":[{}]}}"#,
+                height,
+                events.join(",")
+            )
+        };
+        let body = [block(1, &[diff(1), diff(2)]), "{not json".to_string(), block(2, &[diff(3)])].join("\n");
+        std::fs::write(base.join("hourly/20250101/0"), body + "\n").unwrap();
+
+        let reader = Reader::backfill(base.clone(), 2025010100, Some(2025010100)).await.unwrap();
+        let mut pipeline = ParsePipeline::<BookDiffsByBlock>::spawn_interned(reader, PipelineConfig::default());
+
+        let first = pipeline.next().await.unwrap();
+        let error = pipeline.next().await.unwrap_err();
+        let second = pipeline.next().await.unwrap();
+        assert!(pipeline.next().await.unwrap_err().is::<EndOfStream>());
+        std::fs::remove_dir_all(&base).unwrap();
+
+        let parse_error = error.downcast_ref::<ParseError>().unwrap();
+        assert_eq!((parse_error.line.as_str(), parse_error.position.line), ("{not json", Some(2)));
+
+        let [a, b] = first.events() else { panic!("expected two diffs") };
+        let c = &second.events()[0];
+        assert_eq!((a.oid, b.oid, c.oid, second.height()), (1, 2, 3, 2));
+        for other in [b, c] {
+            assert!(Arc::ptr_eq(&a.coin, &other.coin));
+            assert!(Arc::ptr_eq(&a.user, &other.user));
+        }
+    }
+}
@@ -0,0 +1,71 @@
+// parser/schemas/block.rs
+
+use sonic_rs::{Deserialize, Serialize};
+
+use super::timestamp::{HasTimestamp, Timestamp};
+use super::{BookDiff, BorrowedSchema, Fill, OrderStatus};
+use crate::parser::intern::{Intern, Symbols};
+
+pub type FillsByBlock<N = String> = Block<Fill<N>>;
+pub type OrderStatusesByBlock<N = String> = Block<OrderStatus<N>>;
+pub type BookDiffsByBlock<N = String> = Block<BookDiff<N>>;
+
+// One line of a `node_*_by_block` stream: every record the node produced for a single block.
+#[derive(Debug, Deserialize, Serialize)]
+pub struct Block<T> {
+    pub local_time: Timestamp,
+    pub block_time: Timestamp,
+    pub block_number: u64,
+    pub events: Vec<T>,
+}
+
+impl<T> Block<T> {
+    pub fn height(&self) -> u64 {
+        self.block_number
+    }
+
+    pub fn time(&self) -> Timestamp {
+        self.block_time
+    }
+
+    pub fn events(&self) -> &[T] {
+        &self.events
+    }
+
+    pub fn into_events(self) -> Vec<T> {
+        self.events
+    }
+
+    pub fn len(&self) -> usize {
+        self.events.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.events.is_empty()
+    }
+
+    pub fn map<U>(self, f: impl FnMut(T) -> U) -> Block<U> {
+        Block {
+            local_time: self.local_time,
+            block_time: self.block_time,
+            block_number: self.block_number,
+            events: self.events.into_iter().map(f).collect(),
+        }
+    }
+}
+
+impl<T> HasTimestamp for Block<T> {
+    fn timestamp(&self) -> Option<Timestamp> {
+        Some(self.block_time)
+    }
+}
+
+impl<T: BorrowedSchema> BorrowedSchema for Block<T> {
+    type Ref<'a> = Block<T::Ref<'a>>;
+}
+
+impl<T: Intern> Intern for Block<T> {
+    fn intern(block: Block<T::Ref<'_>>, symbols: &mut Symbols) -> Self {
+        block.map(|event| T::intern(event, symbols))
+    }
+}
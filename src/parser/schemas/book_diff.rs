@@ -1,33 +1,139 @@
+use std::borrow::Cow;
+
 use sonic_rs::{Deserialize, Serialize};
 use super::common::Side;
+use super::timestamp::{HasTimestamp, Timestamp};
+use super::BorrowedSchema;
+use crate::parser::intern::{Intern, Symbol, Symbols};
 
 #[derive(Debug, Deserialize, Serialize)]
-pub struct BookDiff {
-    pub user: String,
+pub struct BookDiff<N = String> {
+    pub user: Symbol,
     pub oid: u64,
-    pub coin: String,
+    pub coin: Symbol,
     pub side: Side,
-    pub px: String,
-    pub raw_book_diff: RawBookDiff,
+    pub px: N,
+    pub raw_book_diff: RawBookDiff<N>,
+}
+
+// Raw diffs carry no time of their own; the block they belong to does.
+impl<N> HasTimestamp for BookDiff<N> {
+    fn timestamp(&self) -> Option<Timestamp> {
+        None
+    }
 }
 
 #[derive(Debug, Deserialize, Serialize)]
 #[serde(untagged)]
-pub enum RawBookDiff {
-    New { new: NewOrder },
-    Update { update: UpdateOrder },
+pub enum RawBookDiff<N = String> {
+    New { new: NewOrder<N> },
+    Update { update: UpdateOrder<N> },
     Remove(String),
 }
 
 #[derive(Debug, Deserialize, Serialize)]
-pub struct NewOrder {
-    pub sz: String,
+pub struct NewOrder<N = String> {
+    pub sz: N,
 }
 
 #[derive(Debug, Deserialize, Serialize)]
-pub struct UpdateOrder {
+pub struct UpdateOrder<N = String> {
     #[serde(rename = "origSz")]
-    pub orig_sz: String,
+    pub orig_sz: N,
     #[serde(rename = "newSz")]
-    pub new_sz: String,
-}
\ No newline at end of file
+    pub new_sz: N,
+}
+
+#[derive(Debug, Deserialize)]
+pub struct BookDiffRef<'a> {
+    #[serde(borrow)]
+    pub user: Cow<'a, str>,
+    pub oid: u64,
+    #[serde(borrow)]
+    pub coin: Cow<'a, str>,
+    pub side: Side,
+    #[serde(borrow)]
+    pub px: Cow<'a, str>,
+    #[serde(borrow)]
+    pub raw_book_diff: RawBookDiffRef<'a>,
+}
+
+#[derive(Debug, Deserialize)]
+#[serde(untagged)]
+pub enum RawBookDiffRef<'a> {
+    New {
+        #[serde(borrow)]
+        new: NewOrderRef<'a>,
+    },
+    Update {
+        #[serde(borrow)]
+        update: UpdateOrderRef<'a>,
+    },
+    Remove(#[serde(borrow)] Cow<'a, str>),
+}
+
+#[derive(Debug, Deserialize)]
+pub struct NewOrderRef<'a> {
+    #[serde(borrow)]
+    pub sz: Cow<'a, str>,
+}
+
+#[derive(Debug, Deserialize)]
+pub struct UpdateOrderRef<'a> {
+    #[serde(borrow, rename = "origSz")]
+    pub orig_sz: Cow<'a, str>,
+    #[serde(borrow, rename = "newSz")]
+    pub new_sz: Cow<'a, str>,
+}
+
+impl BookDiffRef<'_> {
+    pub fn to_owned(&self) -> BookDiff {
+        BookDiff {
+            user: Symbol::from(&*self.user),
+            oid: self.oid,
+            coin: Symbol::from(&*self.coin),
+            side: self.side,
+            px: self.px.to_string(),
+            raw_book_diff: match &self.raw_book_diff {
+                RawBookDiffRef::New { new } => RawBookDiff::New {
+                    new: NewOrder { sz: new.sz.to_string() },
+                },
+                RawBookDiffRef::Update { update } => RawBookDiff::Update {
+                    update: UpdateOrder {
+                        orig_sz: update.orig_sz.to_string(),
+                        new_sz: update.new_sz.to_string(),
+                    },
+                },
+                RawBookDiffRef::Remove(s) => RawBookDiff::Remove(s.to_string()),
+            },
+        }
+    }
+}
+
+impl BorrowedSchema for BookDiff {
+    type Ref<'a> = BookDiffRef<'a>;
+}
+
+impl Intern for BookDiff {
+    fn intern(diff: BookDiffRef<'_>, symbols: &mut Symbols) -> Self {
+        BookDiff {
+            user: symbols.address(&diff.user),
+            oid: diff.oid,
+            coin: symbols.coin(&diff.coin),
+            side: diff.side,
+            px: diff.px.into_owned(),
+            raw_book_diff: match diff.raw_book_diff {
+                RawBookDiffRef::New { new } => RawBookDiff::New {
+                    new: NewOrder { sz: new.sz.into_owned() },
+                },
+                RawBookDiffRef::Update { update } => RawBookDiff::Update {
+                    update: UpdateOrder {
+                        orig_sz: update.orig_sz.into_owned(),
+                        new_sz: update.new_sz.into_owned(),
+                    },
+                },
+                RawBookDiffRef::Remove(s) => RawBookDiff::Remove(s.into_owned()),
+            },
+        }
+    }
+}
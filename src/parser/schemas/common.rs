@@ -1,12 +1,14 @@
 // parser/schemas/common.rs
 
 use sonic_rs::{Deserialize, Serialize};
+use super::timestamp::{self, HasTimestamp, Timestamp};
+use crate::parser::intern::Symbol;
 
 #[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Deserialize, Serialize)]
 pub enum Side {
-    #[serde(alias = "B", alias = "Bid")]
+    #[serde(rename = "B", alias = "Bid")]
     Bid,
-    #[serde(alias = "A", alias = "Ask")]
+    #[serde(rename = "A", alias = "Ask")]
     Ask,
 }
 
@@ -21,29 +23,35 @@ impl Side {
 }
 
 #[derive(Debug, Clone, Deserialize, Serialize)]
-pub struct Order {
-    pub coin: String,
+pub struct Order<N = String> {
+    pub coin: Symbol,
     pub side: Side,
     #[serde(rename = "limitPx")]
-    pub limit_px: String,
-    pub sz: String,
+    pub limit_px: N,
+    pub sz: N,
     pub oid: u64,
-    pub timestamp: u64,
+    #[serde(with = "timestamp::millis")]
+    pub timestamp: Timestamp,
     #[serde(rename = "triggerCondition")]
-    pub trigger_condition: String,
+    pub trigger_condition: Symbol,
     #[serde(rename = "isTrigger")]
     pub is_trigger: bool,
     #[serde(rename = "triggerPx")]
-    pub trigger_px: String,
+    pub trigger_px: N,
     pub children: Vec<sonic_rs::Value>,
     #[serde(rename = "isPositionTpsl")]
     pub is_position_tpsl: bool,
     #[serde(rename = "reduceOnly")]
     pub reduce_only: bool,
     #[serde(rename = "orderType")]
-    pub order_type: String,
+    pub order_type: Symbol,
     #[serde(rename = "origSz")]
-    pub orig_sz: String,
+    pub orig_sz: N,
     pub tif: Option<String>,
     pub cloid: Option<String>,
-}
\ No newline at end of file
+}
+impl<N> HasTimestamp for Order<N> {
+    fn timestamp(&self) -> Option<Timestamp> {
+        Some(self.timestamp)
+    }
+}
@@ -1,37 +1,42 @@
 // src/parser/schemas/fill.rs
 
+use std::borrow::Cow;
+
 use sonic_rs::{Deserialize, Serialize};
 use super::common::Side;
+use super::timestamp::{self, HasTimestamp, Timestamp};
+use super::BorrowedSchema;
 
 #[derive(Debug, Deserialize, Serialize)]
-pub struct Fill(pub String, pub FillData);
+pub struct Fill<N = String>(pub String, pub FillData<N>);
 
-impl Fill {
+impl<N> Fill<N> {
     pub fn user(&self) -> &str {
         &self.0
     }
 
-    pub fn data(&self) -> &FillData {
+    pub fn data(&self) -> &FillData<N> {
         &self.1
     }
 }
 
 #[derive(Debug, Deserialize, Serialize)]
-pub struct FillData {
+pub struct FillData<N = String> {
     pub coin: String,
-    pub px: String,
-    pub sz: String,
+    pub px: N,
+    pub sz: N,
     pub side: Side,
-    pub time: u64,
+    #[serde(with = "timestamp::millis")]
+    pub time: Timestamp,
     #[serde(rename = "startPosition")]
-    pub start_position: String,
+    pub start_position: N,
     pub dir: String,
     #[serde(rename = "closedPnl")]
-    pub closed_pnl: String,
+    pub closed_pnl: N,
     pub hash: String,
     pub oid: u64,
     pub crossed: bool,
-    pub fee: String,
+    pub fee: N,
     pub tid: u64,
     #[serde(rename = "feeToken")]
     pub fee_token: String,
@@ -39,6 +44,92 @@ pub struct FillData {
     #[serde(rename = "twapId")]
     pub twap_id: Option<u64>,
     #[serde(rename = "builderFee")]
-    pub builder_fee: Option<String>,
+    pub builder_fee: Option<N>,
     pub builder: Option<String>,
-}
\ No newline at end of file
+}
+
+impl<N> HasTimestamp for Fill<N> {
+    fn timestamp(&self) -> Option<Timestamp> {
+        Some(self.1.time)
+    }
+}
+
+#[derive(Debug, Deserialize)]
+pub struct FillRef<'a>(#[serde(borrow)] pub Cow<'a, str>, #[serde(borrow)] pub FillDataRef<'a>);
+
+impl<'a> FillRef<'a> {
+    pub fn user(&self) -> &str {
+        &self.0
+    }
+
+    pub fn data(&self) -> &FillDataRef<'a> {
+        &self.1
+    }
+
+    pub fn to_owned(&self) -> Fill {
+        let d = &self.1;
+        Fill(
+            self.0.to_string(),
+            FillData {
+                coin: d.coin.to_string(),
+                px: d.px.to_string(),
+                sz: d.sz.to_string(),
+                side: d.side,
+                time: d.time,
+                start_position: d.start_position.to_string(),
+                dir: d.dir.to_string(),
+                closed_pnl: d.closed_pnl.to_string(),
+                hash: d.hash.to_string(),
+                oid: d.oid,
+                crossed: d.crossed,
+                fee: d.fee.to_string(),
+                tid: d.tid,
+                fee_token: d.fee_token.to_string(),
+                cloid: d.cloid.as_ref().map(|c| c.to_string()),
+                twap_id: d.twap_id,
+                builder_fee: d.builder_fee.as_ref().map(|c| c.to_string()),
+                builder: d.builder.as_ref().map(|c| c.to_string()),
+            },
+        )
+    }
+}
+
+#[derive(Debug, Deserialize)]
+pub struct FillDataRef<'a> {
+    #[serde(borrow)]
+    pub coin: Cow<'a, str>,
+    #[serde(borrow)]
+    pub px: Cow<'a, str>,
+    #[serde(borrow)]
+    pub sz: Cow<'a, str>,
+    pub side: Side,
+    #[serde(with = "timestamp::millis")]
+    pub time: Timestamp,
+    #[serde(borrow, rename = "startPosition")]
+    pub start_position: Cow<'a, str>,
+    #[serde(borrow)]
+    pub dir: Cow<'a, str>,
+    #[serde(borrow, rename = "closedPnl")]
+    pub closed_pnl: Cow<'a, str>,
+    #[serde(borrow)]
+    pub hash: Cow<'a, str>,
+    pub oid: u64,
+    pub crossed: bool,
+    #[serde(borrow)]
+    pub fee: Cow<'a, str>,
+    pub tid: u64,
+    #[serde(borrow, rename = "feeToken")]
+    pub fee_token: Cow<'a, str>,
+    #[serde(borrow)]
+    pub cloid: Option<Cow<'a, str>>,
+    #[serde(rename = "twapId")]
+    pub twap_id: Option<u64>,
+    #[serde(borrow, rename = "builderFee")]
+    pub builder_fee: Option<Cow<'a, str>>,
+    #[serde(borrow)]
+    pub builder: Option<Cow<'a, str>>,
+}
+
+impl BorrowedSchema for Fill {
+    type Ref<'a> = FillRef<'a>;
+}
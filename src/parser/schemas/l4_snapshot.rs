@@ -4,53 +4,53 @@ use sonic_rs::{Deserialize, Serialize};
 use super::common::Order;
 
 #[derive(Debug, Deserialize, Serialize)]
-pub struct L4Snapshot(pub u64, pub Vec<CoinSnapshot>);
+pub struct L4Snapshot<N = String>(pub u64, pub Vec<CoinSnapshot<N>>);
 
-impl L4Snapshot {
+impl<N> L4Snapshot<N> {
     pub fn block_height(&self) -> u64 {
         self.0
     }
 
-    pub fn coins(&self) -> &[CoinSnapshot] {
+    pub fn coins(&self) -> &[CoinSnapshot<N>] {
         &self.1
     }
 }
 
 #[derive(Debug, Deserialize, Serialize)]
-pub struct CoinSnapshot(pub String, pub BookSnapshot);
+pub struct CoinSnapshot<N = String>(pub String, pub BookSnapshot<N>);
 
-impl CoinSnapshot {
+impl<N> CoinSnapshot<N> {
     pub fn coin(&self) -> &str {
         &self.0
     }
 
-    pub fn book(&self) -> &BookSnapshot {
+    pub fn book(&self) -> &BookSnapshot<N> {
         &self.1
     }
 }
 
 #[derive(Debug, Deserialize, Serialize)]
-pub struct BookSnapshot(pub Vec<UserOrder>, pub Vec<UserOrder>);
+pub struct BookSnapshot<N = String>(pub Vec<UserOrder<N>>, pub Vec<UserOrder<N>>);
 
-impl BookSnapshot {
-    pub fn bids(&self) -> &[UserOrder] {
+impl<N> BookSnapshot<N> {
+    pub fn bids(&self) -> &[UserOrder<N>] {
         &self.0
     }
 
-    pub fn asks(&self) -> &[UserOrder] {
+    pub fn asks(&self) -> &[UserOrder<N>] {
         &self.1
     }
 }
 
 #[derive(Debug, Deserialize, Serialize)]
-pub struct UserOrder(pub String, pub Order);
+pub struct UserOrder<N = String>(pub String, pub Order<N>);
 
-impl UserOrder {
+impl<N> UserOrder<N> {
     pub fn user(&self) -> &str {
         &self.0
     }
 
-    pub fn order(&self) -> &Order {
+    pub fn order(&self) -> &Order<N> {
         &self.1
     }
 }
\ No newline at end of file
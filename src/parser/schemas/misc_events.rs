@@ -1,214 +1,221 @@
 // src/parser/schemas/misc_events.rs
 
 use sonic_rs::{Deserialize, Serialize};
+use super::timestamp::{HasTimestamp, Timestamp};
 
 #[derive(Debug, Deserialize, Serialize)]
-pub struct MiscEvent {
-    pub time: String,
+pub struct MiscEvent<N = String> {
+    pub time: Timestamp,
     pub hash: String,
-    pub inner: MiscEventInner,
+    pub inner: MiscEventInner<N>,
+}
+
+impl<N> HasTimestamp for MiscEvent<N> {
+    fn timestamp(&self) -> Option<Timestamp> {
+        Some(self.time)
+    }
 }
 
 #[derive(Debug, Deserialize, Serialize)]
-pub enum MiscEventInner {
-    CDeposit(CDeposit),
-    Delegation(Delegation),
-    CWithdrawal(CWithdrawal),
-    ValidatorRewards(ValidatorRewards),
-    Funding(Funding),
-    LedgerUpdate(LedgerUpdate),
+pub enum MiscEventInner<N = String> {
+    CDeposit(CDeposit<N>),
+    Delegation(Delegation<N>),
+    CWithdrawal(CWithdrawal<N>),
+    ValidatorRewards(ValidatorRewards<N>),
+    Funding(Funding<N>),
+    LedgerUpdate(LedgerUpdate<N>),
 }
 
 #[derive(Debug, Deserialize, Serialize)]
-pub struct CDeposit {
+pub struct CDeposit<N = String> {
     pub user: String,
-    pub amount: String,
+    pub amount: N,
 }
 
 #[derive(Debug, Deserialize, Serialize)]
-pub struct Delegation {
+pub struct Delegation<N = String> {
     pub user: String,
     pub validator: String,
-    pub amount: String,
+    pub amount: N,
     pub is_undelegate: bool,
 }
 
 #[derive(Debug, Deserialize, Serialize)]
-pub struct CWithdrawal {
+pub struct CWithdrawal<N = String> {
     pub user: String,
-    pub amount: String,
+    pub amount: N,
     pub is_finalized: bool,
 }
 
 #[derive(Debug, Deserialize, Serialize)]
-pub struct ValidatorRewards {
-    pub validator_to_reward: Vec<(String, String)>,
+pub struct ValidatorRewards<N = String> {
+    pub validator_to_reward: Vec<(String, N)>,
 }
 
 #[derive(Debug, Deserialize, Serialize)]
-pub struct Funding {
-    pub deltas: Vec<FundingDelta>,
+pub struct Funding<N = String> {
+    pub deltas: Vec<FundingDelta<N>>,
 }
 
 #[derive(Debug, Deserialize, Serialize)]
-pub struct FundingDelta {
+pub struct FundingDelta<N = String> {
     pub user: String,
     pub coin: String,
-    pub funding_amount: String,
-    pub szi: String,
-    pub funding_rate: String,
+    pub funding_amount: N,
+    pub szi: N,
+    pub funding_rate: N,
 }
 
 #[derive(Debug, Deserialize, Serialize)]
-pub struct LedgerUpdate {
+pub struct LedgerUpdate<N = String> {
     pub users: Vec<String>,
-    pub delta: LedgerDelta,
+    pub delta: LedgerDelta<N>,
 }
 
 #[derive(Debug, Deserialize, Serialize)]
 #[serde(tag = "type", rename_all = "camelCase")]
-pub enum LedgerDelta {
-    Withdraw(Withdraw),
-    Deposit(Deposit),
-    VaultCreate(VaultCreate),
-    VaultDeposit(VaultDeposit),
-    VaultWithdraw(VaultWithdraw),
-    VaultDistribution(VaultDistribution),
-    VaultLeaderCommission(VaultLeaderCommission),
-    Liquidation(Liquidation),
-    InternalTransfer(InternalTransfer),
-    SubAccountTransfer(SubAccountTransfer),
-    SpotTransfer(SpotTransfer),
-    SpotGenesis(SpotGenesis),
-    RewardsClaim(RewardsClaim),
-    AccountActivationGas(AccountActivationGas),
-    PerpDexClassTransfer(PerpDexClassTransfer),
-    DeployGasAuction(DeployGasAuction),
-    AccountClassTransfer(AccountClassTransfer),
-    Send(Send),
+pub enum LedgerDelta<N = String> {
+    Withdraw(Withdraw<N>),
+    Deposit(Deposit<N>),
+    VaultCreate(VaultCreate<N>),
+    VaultDeposit(VaultDeposit<N>),
+    VaultWithdraw(VaultWithdraw<N>),
+    VaultDistribution(VaultDistribution<N>),
+    VaultLeaderCommission(VaultLeaderCommission<N>),
+    Liquidation(Liquidation<N>),
+    InternalTransfer(InternalTransfer<N>),
+    SubAccountTransfer(SubAccountTransfer<N>),
+    SpotTransfer(SpotTransfer<N>),
+    SpotGenesis(SpotGenesis<N>),
+    RewardsClaim(RewardsClaim<N>),
+    AccountActivationGas(AccountActivationGas<N>),
+    PerpDexClassTransfer(PerpDexClassTransfer<N>),
+    DeployGasAuction(DeployGasAuction<N>),
+    AccountClassTransfer(AccountClassTransfer<N>),
+    Send(Send<N>),
     #[serde(other)]
     Unknown,
 }
 
 #[derive(Debug, Deserialize, Serialize)]
-pub struct Withdraw {
-    pub usdc: String,
+pub struct Withdraw<N = String> {
+    pub usdc: N,
     pub nonce: u64,
-    pub fee: String,
+    pub fee: N,
 }
 
 #[derive(Debug, Deserialize, Serialize)]
-pub struct Deposit {
-    pub usdc: String,
+pub struct Deposit<N = String> {
+    pub usdc: N,
 }
 
 #[derive(Debug, Deserialize, Serialize)]
-pub struct VaultCreate {
+pub struct VaultCreate<N = String> {
     pub vault: String,
-    pub usdc: String,
-    pub fee: String,
+    pub usdc: N,
+    pub fee: N,
 }
 
 #[derive(Debug, Deserialize, Serialize)]
-pub struct VaultDeposit {
+pub struct VaultDeposit<N = String> {
     pub vault: String,
     pub user: Option<String>,
-    pub usdc: String,
+    pub usdc: N,
 }
 
 #[derive(Debug, Deserialize, Serialize)]
-pub struct VaultWithdraw {
+pub struct VaultWithdraw<N = String> {
     pub vault: String,
     pub user: String,
     #[serde(rename = "requestedUsd")]
-    pub requested_usd: String,
-    pub commission: String,
+    pub requested_usd: N,
+    pub commission: N,
     #[serde(rename = "closingCost")]
-    pub closing_cost: String,
-    pub basis: String,
+    pub closing_cost: N,
+    pub basis: N,
 }
 
 #[derive(Debug, Deserialize, Serialize)]
-pub struct VaultDistribution {
+pub struct VaultDistribution<N = String> {
     pub vault: String,
-    pub usdc: String,
+    pub usdc: N,
 }
 
 #[derive(Debug, Deserialize, Serialize)]
-pub struct VaultLeaderCommission {
+pub struct VaultLeaderCommission<N = String> {
     pub user: String,
-    pub usdc: String,
+    pub usdc: N,
 }
 
 #[derive(Debug, Deserialize, Serialize)]
-pub struct Liquidation {
+pub struct Liquidation<N = String> {
     #[serde(rename = "liquidatedNtlPos")]
-    pub liquidated_ntl_pos: String,
+    pub liquidated_ntl_pos: N,
     #[serde(rename = "accountValue")]
-    pub account_value: String,
+    pub account_value: N,
     #[serde(rename = "leverageType")]
     pub leverage_type: String,
     #[serde(rename = "liquidatedPositions")]
-    pub liquidated_positions: Vec<LiquidatedPosition>,
+    pub liquidated_positions: Vec<LiquidatedPosition<N>>,
 }
 
 #[derive(Debug, Deserialize, Serialize)]
-pub struct LiquidatedPosition {
+pub struct LiquidatedPosition<N = String> {
     pub coin: String,
-    pub szi: String,
+    pub szi: N,
 }
 
 #[derive(Debug, Deserialize, Serialize)]
-pub struct InternalTransfer {
-    pub usdc: String,
+pub struct InternalTransfer<N = String> {
+    pub usdc: N,
     pub user: String,
     pub destination: String,
-    pub fee: String,
+    pub fee: N,
 }
 
 #[derive(Debug, Deserialize, Serialize)]
-pub struct SubAccountTransfer {
-    pub usdc: String,
+pub struct SubAccountTransfer<N = String> {
+    pub usdc: N,
     pub user: String,
     pub destination: String,
 }
 
 #[derive(Debug, Deserialize, Serialize)]
-pub struct SpotTransfer {
+pub struct SpotTransfer<N = String> {
     pub token: String,
-    pub amount: String,
+    pub amount: N,
     #[serde(rename = "usdcValue")]
-    pub usdc_value: String,
+    pub usdc_value: N,
     pub user: String,
     pub destination: String,
-    pub fee: String,
+    pub fee: N,
     #[serde(rename = "nativeTokenFee")]
-    pub native_token_fee: String,
+    pub native_token_fee: N,
     pub nonce: Option<u64>,
     #[serde(rename = "feeToken")]
     pub fee_token: Option<String>,
 }
 
 #[derive(Debug, Deserialize, Serialize)]
-pub struct SpotGenesis {
+pub struct SpotGenesis<N = String> {
     pub token: String,
-    pub amount: String,
+    pub amount: N,
 }
 
 #[derive(Debug, Deserialize, Serialize)]
-pub struct RewardsClaim {
-    pub amount: String,
+pub struct RewardsClaim<N = String> {
+    pub amount: N,
 }
 
 #[derive(Debug, Deserialize, Serialize)]
-pub struct AccountActivationGas {
-    pub amount: String,
+pub struct AccountActivationGas<N = String> {
+    pub amount: N,
     pub token: String,
 }
 
 #[derive(Debug, Deserialize, Serialize)]
-pub struct PerpDexClassTransfer {
-    pub amount: String,
+pub struct PerpDexClassTransfer<N = String> {
+    pub amount: N,
     pub token: String,
     pub dex: String,
     #[serde(rename = "toPerp")]
@@ -216,20 +223,20 @@ pub struct PerpDexClassTransfer {
 }
 
 #[derive(Debug, Deserialize, Serialize)]
-pub struct DeployGasAuction {
+pub struct DeployGasAuction<N = String> {
     pub token: String,
-    pub amount: String,
+    pub amount: N,
 }
 
 #[derive(Debug, Deserialize, Serialize)]
-pub struct AccountClassTransfer {
-    pub usdc: String,
+pub struct AccountClassTransfer<N = String> {
+    pub usdc: N,
     #[serde(rename = "toPerp")]
     pub to_perp: bool,
 }
 
 #[derive(Debug, Deserialize, Serialize)]
-pub struct Send {
+pub struct Send<N = String> {
     pub user: String,
     pub destination: String,
     #[serde(rename = "sourceDex")]
@@ -237,12 +244,12 @@ pub struct Send {
     #[serde(rename = "destinationDex")]
     pub destination_dex: String,
     pub token: String,
-    pub amount: String,
+    pub amount: N,
     #[serde(rename = "usdcValue")]
-    pub usdc_value: String,
-    pub fee: String,
+    pub usdc_value: N,
+    pub fee: N,
     #[serde(rename = "nativeTokenFee")]
-    pub native_token_fee: String,
+    pub native_token_fee: N,
     pub nonce: u64,
     #[serde(rename = "feeToken")]
     pub fee_token: String,
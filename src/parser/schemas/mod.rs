@@ -1,5 +1,6 @@
 // src/parser/schemas/mod.rs
 
+pub mod block;
 pub mod common;
 pub mod trades;
 pub mod order_status;
@@ -9,12 +10,23 @@ pub mod l4_snapshot;
 pub mod fill;
 pub mod twap_status;
 pub mod system_action;
+pub mod timestamp;
+pub mod typed;
 
-pub use trades::Trade;
+pub use block::{Block, BookDiffsByBlock, FillsByBlock, OrderStatusesByBlock};
+pub use trades::{Trade, TradeRef};
 pub use order_status::OrderStatus;
-pub use book_diff::BookDiff;
+pub use book_diff::{BookDiff, BookDiffRef};
 pub use misc_events::MiscEvent;
 pub use l4_snapshot::L4Snapshot;
-pub use fill::Fill;
+pub use fill::{Fill, FillRef};
 pub use twap_status::TwapStatus;
-pub use system_action::SystemAction;
\ No newline at end of file
+pub use system_action::SystemAction;
+pub use timestamp::{HasTimestamp, Timestamp};
+
+// Borrowed views for consumers that finish with a record before reading the next line (see
+// `StreamReader::next_ref`). Records that are kept or handed to another task are built from the
+// borrowed view through `Intern` instead (see `ParsePipeline::spawn_interned`).
+pub trait BorrowedSchema {
+    type Ref<'a>: serde::Deserialize<'a>;
+}
@@ -2,15 +2,22 @@
 
 use sonic_rs::{Deserialize, Serialize};
 use super::common::Order;
+use super::timestamp::{HasTimestamp, Timestamp};
 
 #[derive(Debug, Deserialize, Serialize)]
-pub struct OrderStatus {
-    pub time: String,
+pub struct OrderStatus<N = String> {
+    pub time: Timestamp,
     pub user: String,
     pub hash: Option<String>,
     pub builder: Option<OrderBuilder>,
     pub status: String,
-    pub order: Order,
+    pub order: Order<N>,
+}
+
+impl<N> HasTimestamp for OrderStatus<N> {
+    fn timestamp(&self) -> Option<Timestamp> {
+        Some(self.time)
+    }
 }
 
 #[derive(Debug, Deserialize, Serialize)]
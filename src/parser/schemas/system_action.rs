@@ -1,11 +1,218 @@
 // src/parser/schemas/system_action.rs
 
-use sonic_rs::{Deserialize, Serialize, Value};
+use sonic_rs::{Deserialize, JsonValueTrait, Serialize, Value};
+use super::timestamp::{HasTimestamp, Timestamp};
 
 #[derive(Debug, Deserialize, Serialize)]
 pub struct SystemAction {
     pub user: String,
     pub nonce: u64,
     pub evm_tx_hash: String,
-    pub action: Value,
-}
\ No newline at end of file
+    pub action: Action,
+}
+
+impl HasTimestamp for SystemAction {
+    fn timestamp(&self) -> Option<Timestamp> {
+        None
+    }
+}
+
+// Known actions keep the node's JSON next to the typed view, and both variants serialize back
+// to exactly that JSON, so fields the types don't model are never lost.
+#[derive(Debug)]
+pub enum Action {
+    Known { kind: ActionKind, raw: Value },
+    Unknown(Value),
+}
+
+impl<'de> Deserialize<'de> for Action {
+    fn deserialize<D: serde::Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+        let raw = Value::deserialize(deserializer)?;
+        Ok(match sonic_rs::from_value::<ActionKind>(&raw) {
+            Ok(kind) => Action::Known { kind, raw },
+            Err(_) => Action::Unknown(raw),
+        })
+    }
+}
+
+impl Serialize for Action {
+    fn serialize<S: serde::Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        self.raw().serialize(serializer)
+    }
+}
+
+impl Action {
+    pub fn type_name(&self) -> &str {
+        match self {
+            Action::Known { kind, .. } => kind.type_name(),
+            Action::Unknown(value) => value.get("type").and_then(|v| v.as_str()).unwrap_or("unknown"),
+        }
+    }
+
+    pub fn known(&self) -> Option<&ActionKind> {
+        match self {
+            Action::Known { kind, .. } => Some(kind),
+            Action::Unknown(_) => None,
+        }
+    }
+
+    pub fn raw(&self) -> &Value {
+        match self {
+            Action::Known { raw, .. } | Action::Unknown(raw) => raw,
+        }
+    }
+}
+
+#[derive(Debug, Deserialize, Serialize)]
+#[serde(tag = "type", rename_all = "camelCase")]
+pub enum ActionKind {
+    SpotSend(SpotSend),
+    UsdClassTransfer(UsdClassTransfer),
+    SendAsset(SendAsset),
+    EvmRawTx(EvmRawTx),
+    TokenDelegate(TokenDelegate),
+    CDeposit(StakingTransfer),
+    CWithdraw(StakingTransfer),
+    VaultTransfer(VaultTransfer),
+    Order(PlaceOrder),
+    Cancel(Cancel),
+    CancelByCloid(CancelByCloid),
+    ApproveAgent(ApproveAgent),
+    FinalizeEvmContract(FinalizeEvmContract),
+}
+
+impl ActionKind {
+    pub fn type_name(&self) -> &'static str {
+        match self {
+            ActionKind::SpotSend(_) => "spotSend",
+            ActionKind::UsdClassTransfer(_) => "usdClassTransfer",
+            ActionKind::SendAsset(_) => "sendAsset",
+            ActionKind::EvmRawTx(_) => "evmRawTx",
+            ActionKind::TokenDelegate(_) => "tokenDelegate",
+            ActionKind::CDeposit(_) => "cDeposit",
+            ActionKind::CWithdraw(_) => "cWithdraw",
+            ActionKind::VaultTransfer(_) => "vaultTransfer",
+            ActionKind::Order(_) => "order",
+            ActionKind::Cancel(_) => "cancel",
+            ActionKind::CancelByCloid(_) => "cancelByCloid",
+            ActionKind::ApproveAgent(_) => "approveAgent",
+            ActionKind::FinalizeEvmContract(_) => "finalizeEvmContract",
+        }
+    }
+}
+
+#[derive(Debug, Deserialize, Serialize)]
+pub struct SpotSend {
+    pub destination: String,
+    pub token: String,
+    pub amount: String,
+    pub time: Option<u64>,
+}
+
+#[derive(Debug, Deserialize, Serialize)]
+pub struct UsdClassTransfer {
+    pub amount: String,
+    #[serde(rename = "toPerp")]
+    pub to_perp: bool,
+    pub nonce: Option<u64>,
+}
+
+#[derive(Debug, Deserialize, Serialize)]
+pub struct SendAsset {
+    pub destination: String,
+    #[serde(rename = "sourceDex")]
+    pub source_dex: String,
+    #[serde(rename = "destinationDex")]
+    pub destination_dex: String,
+    pub token: String,
+    pub amount: String,
+    #[serde(rename = "fromSubAccount")]
+    pub from_sub_account: Option<String>,
+    pub nonce: Option<u64>,
+}
+
+#[derive(Debug, Deserialize, Serialize)]
+pub struct EvmRawTx {
+    pub data: String,
+}
+
+#[derive(Debug, Deserialize, Serialize)]
+pub struct TokenDelegate {
+    pub validator: String,
+    pub wei: u64,
+    #[serde(rename = "isUndelegate")]
+    pub is_undelegate: bool,
+    pub nonce: Option<u64>,
+}
+
+#[derive(Debug, Deserialize, Serialize)]
+pub struct StakingTransfer {
+    pub wei: u64,
+    pub nonce: Option<u64>,
+}
+
+#[derive(Debug, Deserialize, Serialize)]
+pub struct VaultTransfer {
+    #[serde(rename = "vaultAddress")]
+    pub vault_address: String,
+    #[serde(rename = "isDeposit")]
+    pub is_deposit: bool,
+    pub usd: u64,
+}
+
+#[derive(Debug, Deserialize, Serialize)]
+pub struct PlaceOrder {
+    pub orders: Vec<Value>,
+    pub grouping: Option<String>,
+}
+
+#[derive(Debug, Deserialize, Serialize)]
+pub struct Cancel {
+    pub cancels: Vec<CancelOid>,
+}
+
+#[derive(Debug, Deserialize, Serialize)]
+pub struct CancelOid {
+    pub a: u32,
+    pub o: u64,
+}
+
+#[derive(Debug, Deserialize, Serialize)]
+pub struct CancelByCloid {
+    pub cancels: Vec<CancelCloid>,
+}
+
+#[derive(Debug, Deserialize, Serialize)]
+pub struct CancelCloid {
+    pub asset: u32,
+    pub cloid: String,
+}
+
+#[derive(Debug, Deserialize, Serialize)]
+pub struct ApproveAgent {
+    #[serde(rename = "agentAddress")]
+    pub agent_address: String,
+    #[serde(rename = "agentName")]
+    pub agent_name: Option<String>,
+    pub nonce: Option<u64>,
+}
+
+#[derive(Debug, Deserialize, Serialize)]
+pub struct FinalizeEvmContract {
+    pub token: u64,
+    pub input: Value,
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn known_actions_serialize_as_the_node_wrote_them() {
+        let line = r#"{"user":"0xabc","nonce":7,"evm_tx_hash":"0x1","action":{"type":"order","orders":[],"grouping":"na","builder":{"b":"0xdef","f":10}}}"#;
+        let action: SystemAction = sonic_rs::from_str(line).unwrap();
+
+        assert!(matches!(action.action.known(), Some(ActionKind::Order(_))));
+        assert_eq!(sonic_rs::to_string(&action).unwrap(), line);
+    }
+}
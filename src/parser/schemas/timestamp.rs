@@ -0,0 +1,156 @@
+// parser/schemas/timestamp.rs
+
+use std::fmt;
+use std::str::FromStr;
+
+use anyhow::{Result, anyhow};
+use chrono::{DateTime, NaiveDateTime, Utc};
+use serde::de::{self, Visitor};
+use serde::{Deserializer, Serializer};
+use sonic_rs::{Deserialize, Serialize};
+
+const NODE_FORMAT: &str = "%Y-%m-%dT%H:%M:%S%.f";
+const NANOS_PER_MILLI: i64 = 1_000_000;
+
+pub trait HasTimestamp {
+    fn timestamp(&self) -> Option<Timestamp>;
+}
+
+// Nanoseconds since the Unix epoch, UTC. Block times arrive as naive "%Y-%m-%dT%H:%M:%S%.f"
+// strings and order/fill times as integer milliseconds; both end up here.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub struct Timestamp(i64);
+
+impl Timestamp {
+    pub const MIN: Timestamp = Timestamp(i64::MIN);
+    pub const MAX: Timestamp = Timestamp(i64::MAX);
+
+    pub fn from_nanos(nanos: i64) -> Self {
+        Self(nanos)
+    }
+
+    pub fn from_millis(millis: i64) -> Self {
+        Self(millis.saturating_mul(NANOS_PER_MILLI))
+    }
+
+    pub fn now() -> Self {
+        Self(Utc::now().timestamp_nanos_opt().unwrap_or(i64::MAX))
+    }
+
+    pub fn as_nanos(&self) -> i64 {
+        self.0
+    }
+
+    pub fn as_millis(&self) -> i64 {
+        self.0.div_euclid(NANOS_PER_MILLI)
+    }
+
+    pub fn to_datetime(&self) -> DateTime<Utc> {
+        DateTime::from_timestamp_nanos(self.0)
+    }
+
+    pub fn saturating_sub(&self, duration: std::time::Duration) -> Self {
+        let nanos = i64::try_from(duration.as_nanos()).unwrap_or(i64::MAX);
+        Self(self.0.saturating_sub(nanos))
+    }
+
+    pub fn saturating_add(&self, duration: std::time::Duration) -> Self {
+        let nanos = i64::try_from(duration.as_nanos()).unwrap_or(i64::MAX);
+        Self(self.0.saturating_add(nanos))
+    }
+
+    // Negative when `earlier` is actually later.
+    pub fn nanos_since(&self, earlier: Timestamp) -> i64 {
+        self.0.saturating_sub(earlier.0)
+    }
+
+    pub fn parse(s: &str) -> Result<Self> {
+        if let Ok(naive) = NaiveDateTime::parse_from_str(s, NODE_FORMAT) {
+            return Self::try_from(naive.and_utc());
+        }
+        let dt = DateTime::parse_from_rfc3339(s).map_err(|e| anyhow!("invalid timestamp {:?}: {}", s, e))?;
+        Self::try_from(dt.with_timezone(&Utc))
+    }
+}
+
+impl TryFrom<DateTime<Utc>> for Timestamp {
+    type Error = anyhow::Error;
+
+    fn try_from(dt: DateTime<Utc>) -> Result<Self> {
+        dt.timestamp_nanos_opt()
+            .map(Self)
+            .ok_or_else(|| anyhow!("timestamp {} out of range", dt))
+    }
+}
+
+impl From<Timestamp> for DateTime<Utc> {
+    fn from(ts: Timestamp) -> Self {
+        ts.to_datetime()
+    }
+}
+
+impl FromStr for Timestamp {
+    type Err = anyhow::Error;
+
+    fn from_str(s: &str) -> Result<Self> {
+        Self::parse(s)
+    }
+}
+
+impl fmt::Display for Timestamp {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{}", self.to_datetime().naive_utc().format(NODE_FORMAT))
+    }
+}
+
+impl Serialize for Timestamp {
+    fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        serializer.collect_str(self)
+    }
+}
+
+impl<'de> Deserialize<'de> for Timestamp {
+    fn deserialize<D: Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+        deserializer.deserialize_any(TimestampVisitor)
+    }
+}
+
+struct TimestampVisitor;
+
+impl Visitor<'_> for TimestampVisitor {
+    type Value = Timestamp;
+
+    fn expecting(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(f, "a datetime string or integer milliseconds")
+    }
+
+    fn visit_str<E: de::Error>(self, v: &str) -> Result<Timestamp, E> {
+        Timestamp::parse(v).map_err(E::custom)
+    }
+
+    fn visit_i64<E: de::Error>(self, v: i64) -> Result<Timestamp, E> {
+        Ok(Timestamp::from_millis(v))
+    }
+
+    fn visit_u64<E: de::Error>(self, v: u64) -> Result<Timestamp, E> {
+        i64::try_from(v)
+            .map(Timestamp::from_millis)
+            .map_err(|_| E::custom(format!("timestamp {} out of range", v)))
+    }
+}
+
+// For fields the node writes as integer milliseconds; keeps them integers on the way back out.
+pub mod millis {
+    use serde::{Deserializer, Serializer};
+    use sonic_rs::Deserialize;
+
+    use super::Timestamp;
+
+    pub fn serialize<S: Serializer>(ts: &Timestamp, serializer: S) -> Result<S::Ok, S::Error> {
+        serializer.serialize_i64(ts.as_millis())
+    }
+
+    pub fn deserialize<'de, D: Deserializer<'de>>(deserializer: D) -> Result<Timestamp, D::Error> {
+        Timestamp::deserialize(deserializer)
+    }
+}
@@ -1,23 +1,88 @@
+use std::borrow::Cow;
+
 use sonic_rs::{Deserialize, Serialize};
 use super::common::Side;
+use super::timestamp::{HasTimestamp, Timestamp};
+use super::BorrowedSchema;
 
 #[derive(Debug, Deserialize, Serialize)]
-pub struct Trade {
+pub struct Trade<N = String> {
     pub coin: String,
     pub side: Side,
-    pub time: String,
-    pub px: String,
-    pub sz: String,
+    pub time: Timestamp,
+    pub px: N,
+    pub sz: N,
     pub hash: String,
     pub trade_dir_override: String,
-    pub side_info: [SideInfo; 2],
+    pub side_info: [SideInfo<N>; 2],
 }
 
 #[derive(Debug, Deserialize, Serialize)]
-pub struct SideInfo {
+pub struct SideInfo<N = String> {
     pub user: String,
-    pub start_pos: String,
+    pub start_pos: N,
     pub oid: u64,
     pub twap_id: Option<u64>,
     pub cloid: Option<String>,
-}
\ No newline at end of file
+}
+
+#[derive(Debug, Deserialize)]
+pub struct TradeRef<'a> {
+    #[serde(borrow)]
+    pub coin: Cow<'a, str>,
+    pub side: Side,
+    pub time: Timestamp,
+    #[serde(borrow)]
+    pub px: Cow<'a, str>,
+    #[serde(borrow)]
+    pub sz: Cow<'a, str>,
+    #[serde(borrow)]
+    pub hash: Cow<'a, str>,
+    #[serde(borrow)]
+    pub trade_dir_override: Cow<'a, str>,
+    #[serde(borrow)]
+    pub side_info: [SideInfoRef<'a>; 2],
+}
+
+#[derive(Debug, Deserialize)]
+pub struct SideInfoRef<'a> {
+    #[serde(borrow)]
+    pub user: Cow<'a, str>,
+    #[serde(borrow)]
+    pub start_pos: Cow<'a, str>,
+    pub oid: u64,
+    pub twap_id: Option<u64>,
+    #[serde(borrow)]
+    pub cloid: Option<Cow<'a, str>>,
+}
+
+impl TradeRef<'_> {
+    pub fn to_owned(&self) -> Trade {
+        Trade {
+            coin: self.coin.to_string(),
+            side: self.side,
+            time: self.time,
+            px: self.px.to_string(),
+            sz: self.sz.to_string(),
+            hash: self.hash.to_string(),
+            trade_dir_override: self.trade_dir_override.to_string(),
+            side_info: self.side_info.each_ref().map(|info| SideInfo {
+                user: info.user.to_string(),
+                start_pos: info.start_pos.to_string(),
+                oid: info.oid,
+                twap_id: info.twap_id,
+                cloid: info.cloid.as_ref().map(|c| c.to_string()),
+            }),
+        }
+    }
+}
+
+impl<N> HasTimestamp for Trade<N> {
+    fn timestamp(&self) -> Option<Timestamp> {
+        Some(self.time)
+    }
+}
+
+impl BorrowedSchema for Trade {
+    type Ref<'a> = TradeRef<'a>;
+}
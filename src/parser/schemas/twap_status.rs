@@ -2,36 +2,44 @@
 
 use sonic_rs::{Deserialize, Serialize};
 use super::common::Side;
+use super::timestamp::{self, HasTimestamp, Timestamp};
 
 #[derive(Debug, Deserialize, Serialize)]
-pub struct TwapStatus {
-    pub time: String,
+pub struct TwapStatus<N = String> {
+    pub time: Timestamp,
     pub twap_id: u64,
-    pub state: TwapState,
+    pub state: TwapState<N>,
     pub status: TwapStatusValue,
 }
 
-impl TwapStatus {
+impl<N> TwapStatus<N> {
     pub fn user(&self) -> &str {
         &self.state.user
     }
 }
 
+impl<N> HasTimestamp for TwapStatus<N> {
+    fn timestamp(&self) -> Option<Timestamp> {
+        Some(self.time)
+    }
+}
+
 #[derive(Debug, Deserialize, Serialize)]
-pub struct TwapState {
+pub struct TwapState<N = String> {
     pub coin: String,
     pub user: String,
     pub side: Side,
-    pub sz: String,
+    pub sz: N,
     #[serde(rename = "executedSz")]
-    pub executed_sz: String,
+    pub executed_sz: N,
     #[serde(rename = "executedNtl")]
-    pub executed_ntl: String,
+    pub executed_ntl: N,
     pub minutes: u64,
     #[serde(rename = "reduceOnly")]
     pub reduce_only: bool,
     pub randomize: bool,
-    pub timestamp: u64,
+    #[serde(with = "timestamp::millis")]
+    pub timestamp: Timestamp,
 }
 
 #[derive(Debug, Deserialize, Serialize)]
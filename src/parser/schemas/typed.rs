@@ -0,0 +1,278 @@
+// parser/schemas/typed.rs
+
+use std::cmp::Ordering;
+use std::fmt;
+use std::hash::{Hash, Hasher};
+use std::str::FromStr;
+
+use rust_decimal::Decimal;
+use rust_decimal::prelude::ToPrimitive;
+use serde::de::{self, Visitor};
+use serde::{Deserializer, Serializer};
+use sonic_rs::{Deserialize, Serialize};
+
+pub type Order = super::common::Order<Num>;
+pub type OrderStatus = super::order_status::OrderStatus<Num>;
+pub type Fill = super::fill::Fill<Num>;
+pub type FillData = super::fill::FillData<Num>;
+pub type Trade = super::trades::Trade<Num>;
+pub type SideInfo = super::trades::SideInfo<Num>;
+pub type BookDiff = super::book_diff::BookDiff<Num>;
+pub type RawBookDiff = super::book_diff::RawBookDiff<Num>;
+pub type TwapStatus = super::twap_status::TwapStatus<Num>;
+pub type TwapState = super::twap_status::TwapState<Num>;
+pub type MiscEvent = super::misc_events::MiscEvent<Num>;
+pub type MiscEventInner = super::misc_events::MiscEventInner<Num>;
+pub type FundingDelta = super::misc_events::FundingDelta<Num>;
+pub type LedgerDelta = super::misc_events::LedgerDelta<Num>;
+pub type L4Snapshot = super::l4_snapshot::L4Snapshot<Num>;
+
+// Only input that prints back exactly as it was read is accepted, so serializing gives the
+// node's own text: scientific notation, floats and non-canonical strings ("+1", "01") are
+// rejected. The node writes "-0.0" for some flat positions and Decimal drops the sign of zero,
+// so that is remembered alongside, as is whether the number arrived as a JSON integer.
+#[derive(Debug, Clone, Copy, Default)]
+pub struct Num {
+    value: Decimal,
+    form: Form,
+}
+
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+enum Form {
+    #[default]
+    Text,
+    NegativeZero,
+    Integer,
+}
+
+impl Num {
+    pub fn new(value: Decimal) -> Self {
+        Self {
+            value,
+            form: Form::Text,
+        }
+    }
+
+    pub fn value(&self) -> Decimal {
+        self.value
+    }
+
+    pub fn is_zero(&self) -> bool {
+        self.value.is_zero()
+    }
+}
+
+impl From<Decimal> for Num {
+    fn from(value: Decimal) -> Self {
+        Self::new(value)
+    }
+}
+
+impl From<Num> for Decimal {
+    fn from(num: Num) -> Self {
+        num.value
+    }
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub enum ParseNumError {
+    Invalid(rust_decimal::Error),
+    NotCanonical,
+}
+
+impl fmt::Display for ParseNumError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::Invalid(e) => write!(f, "{}", e),
+            Self::NotCanonical => write!(f, "would not serialize back to the same text"),
+        }
+    }
+}
+
+impl std::error::Error for ParseNumError {}
+
+impl FromStr for Num {
+    type Err = ParseNumError;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let value = Decimal::from_str(s).map_err(ParseNumError::Invalid)?;
+        let form = if value.is_zero() && s.starts_with('-') {
+            Form::NegativeZero
+        } else {
+            Form::Text
+        };
+        let num = Self { value, form };
+
+        let mut rest = Remaining(s);
+        if fmt::write(&mut rest, format_args!("{}", num)).is_err() || !rest.0.is_empty() {
+            return Err(ParseNumError::NotCanonical);
+        }
+        Ok(num)
+    }
+}
+
+// Checks formatted output against the input piece by piece, without building a `String`.
+struct Remaining<'a>(&'a str);
+
+impl fmt::Write for Remaining<'_> {
+    fn write_str(&mut self, s: &str) -> fmt::Result {
+        self.0 = self.0.strip_prefix(s).ok_or(fmt::Error)?;
+        Ok(())
+    }
+}
+
+impl fmt::Display for Num {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        if self.form == Form::NegativeZero {
+            write!(f, "-")?;
+        }
+        write!(f, "{}", self.value)
+    }
+}
+
+impl PartialEq for Num {
+    fn eq(&self, other: &Self) -> bool {
+        self.value == other.value
+    }
+}
+
+impl Eq for Num {}
+
+impl Hash for Num {
+    fn hash<H: Hasher>(&self, state: &mut H) {
+        self.value.normalize().hash(state)
+    }
+}
+
+impl PartialOrd for Num {
+    fn partial_cmp(&self, other: &Self) -> Option<Ordering> {
+        Some(self.cmp(other))
+    }
+}
+
+impl Ord for Num {
+    fn cmp(&self, other: &Self) -> Ordering {
+        self.value.cmp(&other.value)
+    }
+}
+
+impl Serialize for Num {
+    fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        match self.form {
+            Form::Integer if self.value.is_sign_negative() => match self.value.to_i64() {
+                Some(v) => serializer.serialize_i64(v),
+                None => serializer.collect_str(self),
+            },
+            Form::Integer => match self.value.to_u64() {
+                Some(v) => serializer.serialize_u64(v),
+                None => serializer.collect_str(self),
+            },
+            _ => serializer.collect_str(self),
+        }
+    }
+}
+
+impl<'de> Deserialize<'de> for Num {
+    fn deserialize<D: Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+        deserializer.deserialize_any(NumVisitor)
+    }
+}
+
+struct NumVisitor;
+
+impl Visitor<'_> for NumVisitor {
+    type Value = Num;
+
+    fn expecting(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(f, "a numeric string or integer")
+    }
+
+    fn visit_str<E: de::Error>(self, v: &str) -> Result<Num, E> {
+        Num::from_str(v).map_err(|e| E::custom(format!("invalid decimal {:?}: {}", v, e)))
+    }
+
+    fn visit_i64<E: de::Error>(self, v: i64) -> Result<Num, E> {
+        Ok(Num {
+            value: Decimal::from(v),
+            form: Form::Integer,
+        })
+    }
+
+    fn visit_u64<E: de::Error>(self, v: u64) -> Result<Num, E> {
+        Ok(Num {
+            value: Decimal::from(v),
+            form: Form::Integer,
+        })
+    }
+
+    // By the time a float reaches us its digits are already gone.
+    fn visit_f64<E: de::Error>(self, v: f64) -> Result<Num, E> {
+        Err(E::custom(format!("float {} cannot be read losslessly, expected a numeric string", v)))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn round_trip<T: serde::de::DeserializeOwned + serde::Serialize>(line: &str) {
+        let typed: T = sonic_rs::from_str(line).unwrap_or_else(|e| panic!("{}: {}", e, line));
+        let written = sonic_rs::to_string(&typed).unwrap();
+        let before: sonic_rs::Value = sonic_rs::from_str(line).unwrap();
+        let after: sonic_rs::Value = sonic_rs::from_str(&written).unwrap();
+        assert_eq!(before, after, "\n read: {}\nwrote: {}", line, written);
+    }
+
+    #[test]
+    fn schema_samples_round_trip() {
+        round_trip::<OrderStatus>(
+            r#"{"time":"2025-06-01T12:34:56.789012345","user":"0x31ca8395cf837de08b24da3f660e77761dfb974b","hash":null,"builder":null,"status":"open","order":{"coin":"BTC","side":"B","limitPx":"97123.50","sz":"0.01234","oid":112233445566,"timestamp":1748781296789,"triggerCondition":"N/A","isTrigger":false,"triggerPx":"0.0","children":[],"isPositionTpsl":false,"reduceOnly":false,"orderType":"Limit","origSz":"0.01234","tif":"Alo","cloid":null}}"#,
+        );
+        round_trip::<Fill>(
+            r#"["0x31ca8395cf837de08b24da3f660e77761dfb974b",{"coin":"SOL","px":"187.23","sz":"15.0","side":"B","time":1748781296789,"startPosition":"-0.0","dir":"Open Long","closedPnl":"0.0","hash":"0x9f0d","oid":112233445566,"crossed":true,"fee":"-0.009828","tid":998877665544,"feeToken":"USDC","cloid":null,"twapId":null,"builderFee":"0.0001","builder":null}]"#,
+        );
+        round_trip::<Trade>(
+            r#"{"coin":"ETH","side":"A","time":"2025-06-01T12:34:56.789012345","px":"3456.7","sz":"1.2345","hash":"0x9f0d","trade_dir_override":"Na","side_info":[{"user":"0x31ca","start_pos":"12.5","oid":1,"twap_id":null,"cloid":null},{"user":"0x0104","start_pos":"-3.25","oid":2,"twap_id":7,"cloid":"0x01"}]}"#,
+        );
+        round_trip::<BookDiff>(
+            r#"{"user":"0x31ca","oid":1,"coin":"BTC","side":"B","px":"97123.5","raw_book_diff":{"update":{"origSz":"0.01234","newSz":"0.00567"}}}"#,
+        );
+        round_trip::<TwapStatus>(
+            r#"{"time":"2025-06-01T12:34:56.789012345","twap_id":7,"state":{"coin":"ETH","user":"0x31ca","side":"A","sz":"10.0","executedSz":"2.50","executedNtl":"8641.75","minutes":30,"reduceOnly":false,"randomize":true,"timestamp":1748781296789},"status":"activated"}"#,
+        );
+        round_trip::<MiscEvent>(
+            r#"{"time":"2025-06-01T12:34:56.789012345","hash":"0x9f0d","inner":{"Funding":{"deltas":[{"user":"0x31ca","coin":"BTC","funding_amount":"-0.000123","szi":"-0.0","funding_rate":"0.0000125"}]}}}"#,
+        );
+        round_trip::<MiscEvent>(
+            r#"{"time":"2025-06-01T12:34:56.789012345","hash":"0x9f0d","inner":{"LedgerUpdate":{"users":["0x31ca"],"delta":{"type":"withdraw","usdc":"1000.000000","nonce":42,"fee":"1.0"}}}}"#,
+        );
+    }
+
+    #[test]
+    fn negative_zero_keeps_its_sign() {
+        for text in ["-0.0", "-0", "0.0", "-0.00000"] {
+            let num: Num = text.parse().unwrap();
+            assert!(num.is_zero());
+            assert_eq!(num.to_string(), text);
+            assert_eq!(sonic_rs::to_string(&num).unwrap(), format!("\"{}\"", text));
+        }
+    }
+
+    #[test]
+    fn integers_stay_integers() {
+        for text in ["42", "-7", "0", "18446744073709551615"] {
+            let num: Num = sonic_rs::from_str(text).unwrap();
+            assert_eq!(sonic_rs::to_string(&num).unwrap(), text);
+        }
+    }
+
+    #[test]
+    fn input_that_would_not_round_trip_is_rejected() {
+        for text in ["1e-5", "1E3", "+1", "01", ".5", "1_000", " 1"] {
+            assert!(text.parse::<Num>().is_err(), "{} parsed", text);
+        }
+        assert!(sonic_rs::from_str::<Num>("1.5").is_err());
+        assert!(sonic_rs::from_str::<Num>("1e-5").is_err());
+        assert!(sonic_rs::from_str::<Num>(r#""1.50""#).is_ok());
+    }
+}
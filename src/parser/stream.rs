@@ -1,10 +1,71 @@
 // parser/stream.rs
-use crate::reader::Reader;
+use crate::parser::intern::Intern;
+use crate::parser::schemas::{Block, BorrowedSchema};
+use crate::parser::pipeline::{ParsePipeline, PipelineConfig};
+use crate::reader::{Checkpoint, FileEvent, Position, Reader, ReaderConfig, ReaderHealth};
 use anyhow::Result;
 use serde::de::DeserializeOwned;
+use std::fmt;
 use std::marker::PhantomData;
 use std::path::PathBuf;
 
+#[derive(Debug, Clone)]
+pub struct Positioned<T> {
+    pub position: Position,
+    pub value: T,
+}
+
+impl<T> Positioned<T> {
+    pub fn checkpoint(&self) -> Checkpoint {
+        self.position.checkpoint()
+    }
+
+    pub fn map<U>(self, f: impl FnOnce(T) -> U) -> Positioned<U> {
+        Positioned {
+            position: self.position,
+            value: f(self.value),
+        }
+    }
+}
+
+#[derive(Debug)]
+pub struct ParseError {
+    pub position: Position,
+    pub line: String,
+    pub error: sonic_rs::Error,
+}
+
+impl fmt::Display for ParseError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "failed to parse {}: {}", self.position, self.error)
+    }
+}
+
+impl std::error::Error for ParseError {
+    fn source(&self) -> Option<&(dyn std::error::Error + 'static)> {
+        Some(&self.error)
+    }
+}
+
+pub trait PositionedSource<T>: Send + 'static {
+    fn next_positioned(&mut self) -> impl Future<Output = Result<Positioned<T>>> + Send;
+}
+
+pub(crate) fn parse_event<T: DeserializeOwned>(event: FileEvent) -> Result<Positioned<T>> {
+    match sonic_rs::from_str(&event.line) {
+        Ok(value) => Ok(Positioned {
+            position: event.position,
+            value,
+        }),
+        Err(error) => Err(ParseError {
+            position: event.position,
+            line: event.line,
+            error,
+        }
+        .into()),
+    }
+}
+
 pub struct StreamReader<T> {
     reader: Reader,
     _marker: PhantomData<T>,
@@ -18,8 +79,85 @@ impl<T: DeserializeOwned> StreamReader<T> {
         })
     }
 
+    pub async fn resume(path: PathBuf, checkpoint: Option<Checkpoint>) -> Result<Self> {
+        Ok(Self {
+            reader: Reader::resume(path, checkpoint).await?,
+            _marker: PhantomData,
+        })
+    }
+
+    pub async fn backfill(path: PathBuf, start: u64, end: Option<u64>) -> Result<Self> {
+        Ok(Self {
+            reader: Reader::backfill(path, start, end).await?,
+            _marker: PhantomData,
+        })
+    }
+
+    pub fn with_config(mut self, config: ReaderConfig) -> Self {
+        self.reader = self.reader.with_config(config);
+        self
+    }
+
+    pub async fn health(&self) -> Result<ReaderHealth> {
+        self.reader.health().await
+    }
+
+    pub fn checkpoint(&self) -> Option<Checkpoint> {
+        self.reader.checkpoint()
+    }
+
+    pub fn into_pipeline(self, config: PipelineConfig) -> ParsePipeline<T>
+    where
+        T: Send + 'static,
+    {
+        ParsePipeline::spawn(self.reader, config)
+    }
+
+    pub fn into_interned_pipeline(self, config: PipelineConfig) -> ParsePipeline<T>
+    where
+        T: Intern + Send + 'static,
+    {
+        ParsePipeline::spawn_interned(self.reader, config)
+    }
+
+    pub async fn next_ref<R>(&mut self, f: impl FnOnce(T::Ref<'_>, &Position) -> R) -> Result<R>
+    where
+        T: BorrowedSchema,
+    {
+        self.reader
+            .next_line_with(|line, position| match sonic_rs::from_str(line) {
+                Ok(value) => Ok(f(value, position)),
+                Err(error) => Err(ParseError {
+                    position: position.clone(),
+                    line: line.to_string(),
+                    error,
+                }
+                .into()),
+            })
+            .await?
+    }
+
     pub async fn next(&mut self) -> Result<T> {
+        self.next_positioned().await.map(|p| p.value)
+    }
+
+    pub async fn next_positioned(&mut self) -> Result<Positioned<T>> {
         let event = self.reader.next_event().await?;
-        sonic_rs::from_str(&event.line).map_err(Into::into)
+        parse_event(event)
+    }
+}
+// Reads a `node_*_by_block` stream one block per line; `next_ref` works here as well and
+// borrows every event of the block from the line buffer.
+pub type BlockStreamReader<T> = StreamReader<Block<T>>;
+
+impl<T: DeserializeOwned> StreamReader<Block<T>> {
+    pub async fn next_block(&mut self) -> Result<Positioned<Block<T>>> {
+        self.next_positioned().await
+    }
+}
+
+impl<T: DeserializeOwned + Send + 'static> PositionedSource<T> for StreamReader<T> {
+    async fn next_positioned(&mut self) -> Result<Positioned<T>> {
+        StreamReader::next_positioned(self).await
     }
-}
\ No newline at end of file
+}
@@ -0,0 +1,82 @@
+use std::os::unix::fs::MetadataExt;
+use std::path::{Path, PathBuf};
+
+use anyhow::{Context, Result, anyhow};
+use sonic_rs::{Deserialize, Serialize};
+use tokio::fs;
+
+#[derive(Debug, Clone, PartialEq, Eq, Deserialize, Serialize)]
+pub struct Checkpoint {
+    pub path: PathBuf,
+    pub offset: u64,
+    pub partial: String,
+    #[serde(default)]
+    pub line: Option<u64>,
+    #[serde(default)]
+    pub file_id: Option<FileId>,
+}
+
+// Which file an offset belongs to, so a path replaced while we were down is not resumed mid-way.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Deserialize, Serialize)]
+pub struct FileId {
+    pub dev: u64,
+    pub ino: u64,
+}
+
+impl FileId {
+    pub fn of(meta: &std::fs::Metadata) -> Self {
+        Self {
+            dev: meta.dev(),
+            ino: meta.ino(),
+        }
+    }
+}
+
+pub struct CheckpointStore {
+    dir: PathBuf,
+}
+
+impl CheckpointStore {
+    pub async fn open(dir: impl Into<PathBuf>) -> Result<Self> {
+        let dir = dir.into();
+        fs::create_dir_all(&dir)
+            .await
+            .with_context(|| format!("failed to create checkpoint dir {}", dir.display()))?;
+        Ok(Self { dir })
+    }
+
+    pub async fn load(&self, stream: &Path) -> Result<Option<Checkpoint>> {
+        let path = self.file_for(stream)?;
+        if !path.exists() {
+            return Ok(None);
+        }
+
+        let bytes = fs::read(&path)
+            .await
+            .with_context(|| format!("failed to read checkpoint {}", path.display()))?;
+        let checkpoint = sonic_rs::from_slice(&bytes)
+            .with_context(|| format!("failed to parse checkpoint {}", path.display()))?;
+
+        Ok(Some(checkpoint))
+    }
+
+    pub async fn save(&self, stream: &Path, checkpoint: &Checkpoint) -> Result<()> {
+        let path = self.file_for(stream)?;
+        let tmp = path.with_extension("json.tmp");
+
+        fs::write(&tmp, sonic_rs::to_vec(checkpoint)?).await?;
+        fs::rename(&tmp, &path)
+            .await
+            .with_context(|| format!("failed to commit checkpoint {}", path.display()))?;
+
+        Ok(())
+    }
+
+    fn file_for(&self, stream: &Path) -> Result<PathBuf> {
+        let name = stream
+            .file_name()
+            .and_then(|n| n.to_str())
+            .ok_or_else(|| anyhow!("invalid stream path {}", stream.display()))?;
+        Ok(self.dir.join(format!("{}.json", name)))
+    }
+}
@@ -0,0 +1,55 @@
+use std::path::Path;
+
+use async_compression::tokio::bufread::{GzipDecoder, Lz4Decoder, ZstdDecoder};
+use tokio::fs::File;
+use tokio::io::{AsyncBufRead, BufReader};
+
+pub type HourlyReader = Box<dyn AsyncBufRead + Send + Sync + Unpin>;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Compression {
+    None,
+    Lz4,
+    Zstd,
+    Gzip,
+}
+
+impl Compression {
+    pub fn from_path(path: &Path) -> Self {
+        match path.extension().and_then(|e| e.to_str()) {
+            Some("lz4") => Self::Lz4,
+            Some("zst") => Self::Zstd,
+            Some("gz") => Self::Gzip,
+            _ => Self::None,
+        }
+    }
+
+    pub fn is_compressed(self) -> bool {
+        self != Self::None
+    }
+
+    pub fn strip(name: &str) -> &str {
+        [".lz4", ".zst", ".gz"]
+            .iter()
+            .find_map(|ext| name.strip_suffix(ext))
+            .unwrap_or(name)
+    }
+
+    pub fn wrap(self, file: File) -> HourlyReader {
+        let file = BufReader::new(file);
+        match self {
+            Self::None => Box::new(file),
+            Self::Lz4 => Box::new(BufReader::new(Lz4Decoder::new(file))),
+            Self::Zstd => {
+                let mut decoder = ZstdDecoder::new(file);
+                decoder.multiple_members(true);
+                Box::new(BufReader::new(decoder))
+            }
+            Self::Gzip => {
+                let mut decoder = GzipDecoder::new(file);
+                decoder.multiple_members(true);
+                Box::new(BufReader::new(decoder))
+            }
+        }
+    }
+}
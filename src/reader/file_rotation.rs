@@ -3,9 +3,48 @@ use std::path::{Path, PathBuf};
 use anyhow::{Result, anyhow};
 use tokio::fs;
 
+use crate::reader::compression::Compression;
+
 pub async fn find_latest_file(base_path: &Path) -> Result<PathBuf> {
+    list_hourly_files(base_path, 0)
+        .await?
+        .pop()
+        .map(|(_, path)| path)
+        .ok_or_else(|| anyhow!("No hourly files in {}", base_path.join("hourly").display()))
+}
+
+pub async fn require_hourly_dir(base_path: &Path) -> Result<()> {
     let hourly_path = base_path.join("hourly");
-    let mut latest: Option<(u64, PathBuf)> = None;
+    match fs::metadata(&hourly_path).await {
+        Ok(meta) if meta.is_dir() => Ok(()),
+        Ok(_) => Err(anyhow!("{} is not a directory", hourly_path.display())),
+        Err(e) => Err(anyhow!("{} is not readable: {}", hourly_path.display(), e)),
+    }
+}
+
+pub async fn files_after(base_path: &Path, after: u64) -> Result<Vec<PathBuf>> {
+    Ok(list_hourly_files(base_path, after / 100)
+        .await?
+        .into_iter()
+        .filter(|(ts, _)| *ts > after)
+        .map(|(_, path)| path)
+        .collect())
+}
+
+pub async fn files_in_range(base_path: &Path, start: u64, end: Option<u64>) -> Result<Vec<PathBuf>> {
+    Ok(list_hourly_files(base_path, start / 100)
+        .await?
+        .into_iter()
+        .filter(|(ts, _)| *ts >= start && end.is_none_or(|end| *ts <= end))
+        .map(|(_, path)| path)
+        .collect())
+}
+
+// Date directories before `from_date` (YYYYMMDD) are skipped without being listed, so polling
+// stays cheap however much history sits under `hourly/`.
+pub async fn list_hourly_files(base_path: &Path, from_date: u64) -> Result<Vec<(u64, PathBuf)>> {
+    let hourly_path = base_path.join("hourly");
+    let mut files = Vec::new();
 
     let mut date_dirs = fs::read_dir(&hourly_path).await?;
     while let Some(date_entry) = date_dirs.next_entry().await? {
@@ -17,6 +56,9 @@ pub async fn find_latest_file(base_path: &Path) -> Result<PathBuf> {
         let Some(date) = parse_component(date_path.file_name()) else {
             continue;
         };
+        if date < from_date {
+            continue;
+        }
 
         let mut hour_files = fs::read_dir(&date_path).await?;
         while let Some(hour_entry) = hour_files.next_entry().await? {
@@ -33,16 +75,25 @@ pub async fn find_latest_file(base_path: &Path) -> Result<PathBuf> {
                 continue;
             }
 
-            let ts = date * 100 + hour;
-            if latest.as_ref().is_none_or(|(best, _)| ts > *best) {
-                latest = Some((ts, hour_path));
-            }
+            files.push((date * 100 + hour, hour_path));
         }
     }
 
-    latest
-        .map(|(_, path)| path)
-        .ok_or_else(|| anyhow!("No hourly files in {}", hourly_path.display()))
+    files.sort_by_key(|(ts, path)| (*ts, Compression::from_path(path).is_compressed()));
+    files.dedup_by_key(|(ts, _)| *ts);
+    Ok(files)
+}
+
+// The same hour under another compression, e.g. `5.zst` once `5` has been compressed away.
+pub async fn find_sibling(path: &Path) -> Option<PathBuf> {
+    let stem = Compression::strip(path.file_name()?.to_str()?);
+    for ext in ["", ".lz4", ".zst", ".gz"] {
+        let candidate = path.with_file_name(format!("{}{}", stem, ext));
+        if candidate != path && fs::metadata(&candidate).await.is_ok_and(|m| m.is_file()) {
+            return Some(candidate);
+        }
+    }
+    None
 }
 
 pub fn extract_timestamp(path: &Path) -> Option<u64> {
@@ -51,10 +102,46 @@ pub fn extract_timestamp(path: &Path) -> Option<u64> {
     (hour < 24).then_some(date * 100 + hour)
 }
 
+pub fn parse_hour(spec: &str) -> Option<u64> {
+    let (date, hour) = spec.trim_matches('/').split_once('/')?;
+    if date.len() != 8 {
+        return None;
+    }
+    let date: u64 = date.parse().ok()?;
+    let hour: u64 = hour.parse().ok()?;
+    (hour < 24).then_some(date * 100 + hour)
+}
+
 pub fn is_valid_hourly_file(path: &Path) -> bool {
     extract_timestamp(path).is_some()
 }
 
 fn parse_component(s: Option<&std::ffi::OsStr>) -> Option<u64> {
-    s?.to_str()?.parse().ok()
-}
\ No newline at end of file
+    Compression::strip(s?.to_str()?).parse().ok()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[tokio::test]
+    async fn files_after_crosses_into_later_dates() {
+        let base = std::env::temp_dir().join(format!("hl-rotation-{}", uuid::Uuid::new_v4()));
+        for (date, hour) in [("20241231", "23"), ("20250101", "22"), ("20250101", "23"), ("20250102", "0")] {
+            std::fs::create_dir_all(base.join("hourly").join(date)).unwrap();
+            std::fs::write(base.join("hourly").join(date).join(hour), "").unwrap();
+        }
+
+        let after: Vec<_> = files_after(&base, 2025010122)
+            .await
+            .unwrap()
+            .iter()
+            .filter_map(|p| extract_timestamp(p))
+            .collect();
+        let all = list_hourly_files(&base, 0).await.unwrap().len();
+        std::fs::remove_dir_all(&base).unwrap();
+
+        assert_eq!(after, vec![2025010123, 2025010200]);
+        assert_eq!(all, 4);
+    }
+}
@@ -1,5 +1,14 @@
+mod checkpoint;
+mod compression;
 mod file_rotation;
+mod position;
 mod tracked_file;
+#[allow(clippy::module_inception)]
 mod reader;
 
-pub use reader::{Reader, FileEvent};
\ No newline at end of file
+pub use checkpoint::{Checkpoint, CheckpointStore, FileId};
+pub use compression::Compression;
+pub use file_rotation::{parse_hour, require_hourly_dir};
+pub use position::Position;
+pub use tracked_file::ResetKind;
+pub use reader::{EndOfStream, FileEvent, FileReset, LineTooLong, Reader, ReaderConfig, ReaderHealth, WatchMode};
\ No newline at end of file
@@ -0,0 +1,40 @@
+use std::path::Path;
+use std::sync::Arc;
+
+use chrono::{DateTime, Utc};
+use sonic_rs::{Deserialize, Serialize};
+
+use crate::reader::checkpoint::{Checkpoint, FileId};
+
+#[derive(Debug, Clone, PartialEq, Eq, Deserialize, Serialize)]
+pub struct Position {
+    pub path: Arc<Path>,
+    pub hour: Option<u64>,
+    pub offset: u64,
+    pub next_offset: u64,
+    pub line: Option<u64>,
+    pub read_at: DateTime<Utc>,
+    #[serde(default)]
+    pub file_id: Option<FileId>,
+}
+
+impl Position {
+    pub fn checkpoint(&self) -> Checkpoint {
+        Checkpoint {
+            path: self.path.to_path_buf(),
+            offset: self.next_offset,
+            partial: String::new(),
+            line: self.line,
+            file_id: self.file_id,
+        }
+    }
+}
+
+impl std::fmt::Display for Position {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self.line {
+            Some(line) => write!(f, "{}:{} (offset {})", self.path.display(), line, self.offset),
+            None => write!(f, "{} (offset {})", self.path.display(), self.offset),
+        }
+    }
+}
@@ -1,74 +1,507 @@
 use std::collections::VecDeque;
-use std::path::PathBuf;
+use std::fmt;
+use std::path::{Path, PathBuf};
+use std::sync::Arc;
+use std::sync::atomic::{AtomicBool, Ordering};
 
 use anyhow::{Result, anyhow};
+use chrono::Utc;
 use notify::{Event, RecommendedWatcher, RecursiveMode, Watcher};
-use tokio::sync::mpsc::{UnboundedReceiver, unbounded_channel};
+use tokio::sync::mpsc::{Receiver, channel};
+use tokio::time::{Duration, Instant, sleep, timeout};
 
-use crate::reader::file_rotation::{extract_timestamp, find_latest_file, is_valid_hourly_file};
-use crate::reader::tracked_file::TrackedFile;
+use crate::reader::checkpoint::{Checkpoint, FileId};
+use crate::reader::file_rotation::{
+    extract_timestamp, files_after, files_in_range, find_latest_file, find_sibling, is_valid_hourly_file,
+};
+use crate::reader::position::Position;
+use crate::reader::tracked_file::{Line, ResetKind, Span, TrackedFile};
 
 pub struct FileEvent {
-    pub source: PathBuf,
     pub line: String,
+    pub position: Position,
 }
 
-pub struct Reader {
-    base_path: PathBuf,
-    file: Option<TrackedFile>,
-    fs_rx: UnboundedReceiver<notify::Result<Event>>,
-    pending: VecDeque<FileEvent>,
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct FileReset {
+    pub path: PathBuf,
+    pub kind: ResetKind,
+}
+
+impl fmt::Display for FileReset {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let what = match self.kind {
+            ResetKind::Truncated => "truncated",
+            ResetKind::Replaced => "replaced",
+        };
+        write!(f, "{} was {}, reading restarted from the beginning", self.path.display(), what)
+    }
+}
+
+impl std::error::Error for FileReset {}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct LineTooLong {
+    pub position: Position,
+    pub len: usize,
+}
+
+impl fmt::Display for LineTooLong {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "skipped {} byte line at {}", self.len, self.position)
+    }
+}
+
+impl std::error::Error for LineTooLong {}
+
+enum Pending {
+    Line(FileEvent),
+    Reset(FileReset),
+    TooLong(LineTooLong),
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct EndOfStream;
+
+impl fmt::Display for EndOfStream {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "end of backfill range")
+    }
+}
+
+impl std::error::Error for EndOfStream {}
+
+#[derive(Debug, Clone, Copy)]
+pub enum WatchMode {
+    Notify,
+    Poll { interval: Duration },
+    Hybrid { interval: Duration, idle: Duration },
+}
+
+#[derive(Debug, Clone)]
+pub struct ReaderConfig {
+    pub watch_mode: WatchMode,
+    pub lag_threshold: Duration,
+    pub batch_size: usize,
+    pub max_line_len: usize,
+    pub event_buffer: usize,
+}
+
+impl Default for ReaderConfig {
+    fn default() -> Self {
+        Self {
+            watch_mode: WatchMode::Hybrid {
+                interval: Duration::from_secs(1),
+                idle: Duration::from_secs(5),
+            },
+            lag_threshold: Duration::from_secs(10),
+            batch_size: 4096,
+            max_line_len: 16 * 1024 * 1024,
+            event_buffer: 1024,
+        }
+    }
+}
+
+#[derive(Debug, Clone)]
+pub struct ReaderHealth {
+    pub file: Option<PathBuf>,
+    pub last_fs_event: Option<Instant>,
+    pub last_line: Option<Instant>,
+    pub lag_bytes: u64,
+    pub stalled: bool,
+}
+
+struct FsWatch {
+    rx: Receiver<notify::Result<Event>>,
+    overflowed: Arc<AtomicBool>,
     _watcher: RecommendedWatcher,
 }
 
-impl Reader {
-    pub async fn new(base_path: PathBuf) -> Result<Self> {
-        let (tx, rx) = unbounded_channel();
+impl FsWatch {
+    fn new(base_path: &Path, capacity: usize) -> Result<Self> {
+        let (tx, rx) = channel(capacity.max(1));
+        let overflowed = Arc::new(AtomicBool::new(false));
+
+        let flag = overflowed.clone();
         let mut watcher = notify::recommended_watcher(move |res| {
-            let _ = tx.send(res);
+            if tx.try_send(res).is_err() {
+                flag.store(true, Ordering::Relaxed);
+            }
         })?;
 
         let hourly_dir = base_path.join("hourly");
-        let file = match find_latest_file(&base_path).await {
-            Ok(latest) => Some(TrackedFile::open_at_end(latest).await?),
-            Err(_) => None,
-        };
-
         if hourly_dir.exists() {
             let canonical = hourly_dir.canonicalize()?;
             watcher.watch(&canonical, RecursiveMode::Recursive)?;
         }
 
         Ok(Self {
-            base_path,
-            file,
-            fs_rx: rx,
-            pending: VecDeque::new(),
+            rx,
+            overflowed,
             _watcher: watcher,
         })
     }
+}
+
+pub struct Reader {
+    base_path: PathBuf,
+    config: ReaderConfig,
+    file: Option<TrackedFile>,
+    dirty: bool,
+    queue: VecDeque<PathBuf>,
+    rotate_to: Option<PathBuf>,
+    follow: bool,
+    floor: u64,
+    live: bool,
+    watch: Option<FsWatch>,
+    pending: VecDeque<Pending>,
+    spare: Vec<String>,
+    position: Option<Checkpoint>,
+    last_fs_event: Option<Instant>,
+    last_line: Option<Instant>,
+    quiet_since: Instant,
+    polling_fallback: bool,
+}
+
+impl Reader {
+    pub async fn new(base_path: PathBuf) -> Result<Self> {
+        let mut reader = Self::unopened(base_path, true);
+
+        if let Ok(latest) = find_latest_file(&reader.base_path).await {
+            let tracked = TrackedFile::open_at_end(latest).await?;
+            reader.position = Some(tracked.checkpoint());
+            reader.file = Some(tracked);
+        }
+
+        Ok(reader)
+    }
+
+    pub async fn resume(base_path: PathBuf, checkpoint: Option<Checkpoint>) -> Result<Self> {
+        let Some(checkpoint) = checkpoint else {
+            return Self::new(base_path).await;
+        };
+
+        let mut reader = Self::unopened(base_path, true);
+
+        if checkpoint.path.is_file() {
+            let current = FileId::of(&tokio::fs::metadata(&checkpoint.path).await?);
+            let tracked = match checkpoint.file_id {
+                Some(saved) if saved != current => {
+                    tracing::warn!(
+                        "{} was replaced since the checkpoint, reading it from the beginning",
+                        checkpoint.path.display()
+                    );
+                    reader.pending.push_back(Pending::Reset(FileReset {
+                        path: checkpoint.path.clone(),
+                        kind: ResetKind::Replaced,
+                    }));
+                    TrackedFile::open(checkpoint.path.clone()).await?
+                }
+                _ => {
+                    TrackedFile::open_at(
+                        checkpoint.path.clone(),
+                        checkpoint.offset,
+                        checkpoint.partial.clone(),
+                        checkpoint.line,
+                    )
+                    .await?
+                }
+            };
+            reader.file = Some(tracked);
+            reader.dirty = true;
+        } else if let Some(sibling) = find_sibling(&checkpoint.path).await {
+            // Compression keeps the content, so the offset carries over in decompressed bytes.
+            tracing::warn!(
+                "checkpoint file {} is gone, resuming from {} at the same offset",
+                checkpoint.path.display(),
+                sibling.display()
+            );
+            let tracked = TrackedFile::open_at(
+                sibling,
+                checkpoint.offset,
+                checkpoint.partial.clone(),
+                checkpoint.line,
+            )
+            .await?;
+            reader.file = Some(tracked);
+            reader.dirty = true;
+        } else {
+            tracing::warn!(
+                "checkpoint file {} is gone, resuming from the next hourly file",
+                checkpoint.path.display()
+            );
+        }
+
+        reader.floor = extract_timestamp(&checkpoint.path).unwrap_or(0);
+        reader.position = Some(checkpoint);
+
+        Ok(reader)
+    }
 
+    pub async fn backfill(base_path: PathBuf, start: u64, end: Option<u64>) -> Result<Self> {
+        let mut reader = Self::unopened(base_path, end.is_none());
+        reader.floor = start.saturating_sub(1);
+        reader.queue = files_in_range(&reader.base_path, start, end).await?.into();
+
+        tracing::info!(
+            "backfilling {} hourly files in {} from {}{}",
+            reader.queue.len(),
+            reader.base_path.display(),
+            start,
+            end.map(|e| format!(" to {}", e)).unwrap_or_else(|| " then following live".into())
+        );
+
+        Ok(reader)
+    }
+
+    pub fn with_config(mut self, config: ReaderConfig) -> Self {
+        self.config = config;
+        self
+    }
+
+    fn unopened(base_path: PathBuf, follow: bool) -> Self {
+        Self {
+            base_path,
+            config: ReaderConfig::default(),
+            file: None,
+            dirty: false,
+            queue: VecDeque::new(),
+            rotate_to: None,
+            follow,
+            floor: 0,
+            live: false,
+            watch: None,
+            pending: VecDeque::new(),
+            spare: Vec::new(),
+            position: None,
+            last_fs_event: None,
+            last_line: None,
+            quiet_since: Instant::now(),
+            polling_fallback: false,
+        }
+    }
+
+    // Cancel safe: everything read lives in `pending` or the tracked file, so it can sit in a
+    // `select!` next to timers without dropping lines or moving the checkpoint past them.
     pub async fn next_event(&mut self) -> Result<FileEvent> {
         loop {
-            if let Some(event) = self.pending.pop_front() {
-                return Ok(event);
+            match self.pop_pending() {
+                Some(Pending::Line(event)) => return Ok(event),
+                Some(Pending::Reset(reset)) => return Err(reset.into()),
+                Some(Pending::TooLong(too_long)) => return Err(too_long.into()),
+                None => {}
+            }
+
+            if self.dirty {
+                self.read_batch().await?;
+                continue;
             }
 
-            match self.fs_rx.recv().await {
-                Some(Ok(event)) => self.handle_fs_event(event).await?,
-                Some(Err(e)) => return Err(e.into()),
-                None => return Err(anyhow!("File watcher channel closed")),
+            if let Some(target) = self.rotate_to.clone() {
+                self.advance_to(target).await?;
+                continue;
             }
+
+            if let Some(next) = self.queue.front().cloned() {
+                self.switch_to(next).await?;
+                continue;
+            }
+
+            if !self.follow {
+                return Err(EndOfStream.into());
+            }
+
+            if !self.live {
+                self.start_live().await?;
+                continue;
+            }
+
+            self.wait_for_change().await?;
+        }
+    }
+
+    pub async fn next_batch(&mut self) -> Result<Vec<FileEvent>> {
+        let first = self.next_event().await?;
+
+        let mut batch = Vec::with_capacity(self.pending.len() + 1);
+        batch.push(first);
+        while let Some(event) = self.try_next_event() {
+            batch.push(event);
+        }
+
+        Ok(batch)
+    }
+
+    pub async fn next_line_with<R>(&mut self, f: impl FnOnce(&str, &Position) -> R) -> Result<R> {
+        let event = self.next_event().await?;
+        let result = f(&event.line, &event.position);
+        self.recycle(event.line);
+        Ok(result)
+    }
+
+    pub fn recycle(&mut self, line: String) {
+        if self.spare.len() < self.config.batch_size {
+            self.spare.push(line);
         }
     }
 
     pub fn try_next_event(&mut self) -> Option<FileEvent> {
-        self.pending.pop_front()
+        if !matches!(self.pending.front(), Some(Pending::Line(_))) {
+            return None;
+        }
+
+        match self.pop_pending()? {
+            Pending::Line(event) => Some(event),
+            _ => None,
+        }
+    }
+
+    pub fn checkpoint(&self) -> Option<Checkpoint> {
+        match &self.file {
+            Some(file) if self.pending.is_empty() => Some(file.checkpoint()),
+            _ => self.position.clone(),
+        }
+    }
+
+    fn pop_pending(&mut self) -> Option<Pending> {
+        let item = self.pending.pop_front()?;
+        self.position = Some(match &item {
+            Pending::Line(event) => event.position.checkpoint(),
+            Pending::TooLong(too_long) => too_long.position.checkpoint(),
+            Pending::Reset(reset) => Checkpoint {
+                path: reset.path.clone(),
+                offset: 0,
+                partial: String::new(),
+                line: Some(0),
+                file_id: self.file.as_ref().map(|f| f.id()),
+            },
+        });
+        Some(item)
+    }
+
+    pub async fn health(&self) -> Result<ReaderHealth> {
+        let (file, lag_bytes) = match &self.file {
+            Some(f) => (Some(f.path().to_path_buf()), f.unread_bytes().await?),
+            None => (None, 0),
+        };
+
+        let stalled = lag_bytes > 0
+            && self
+                .last_line
+                .is_none_or(|t| t.elapsed() > self.config.lag_threshold);
+
+        Ok(ReaderHealth {
+            file,
+            last_fs_event: self.last_fs_event,
+            last_line: self.last_line,
+            lag_bytes,
+            stalled,
+        })
+    }
+
+    async fn start_live(&mut self) -> Result<()> {
+        if !matches!(self.config.watch_mode, WatchMode::Poll { .. }) {
+            self.watch = Some(FsWatch::new(&self.base_path, self.config.event_buffer)?);
+        }
+        self.quiet_since = Instant::now();
+        self.poll().await?;
+        self.live = true;
+        Ok(())
+    }
+
+    async fn wait_for_change(&mut self) -> Result<()> {
+        let idle = match self.config.watch_mode {
+            WatchMode::Notify => None,
+            WatchMode::Poll { interval } => {
+                sleep(interval).await;
+                return self.poll().await;
+            }
+            WatchMode::Hybrid { interval, idle } => {
+                let quiet = self.quiet_since.elapsed();
+                Some(if quiet >= idle { interval } else { idle - quiet })
+            }
+        };
+
+        let Some(watch) = &mut self.watch else {
+            return Err(anyhow!("File watcher not started"));
+        };
+
+        if watch.overflowed.swap(false, Ordering::Relaxed) {
+            tracing::debug!("watcher queue for {} overflowed, polling", self.base_path.display());
+            return self.poll().await;
+        }
+
+        let received = match idle {
+            Some(wait) => match timeout(wait, watch.rx.recv()).await {
+                Ok(received) => received,
+                Err(_) => return self.poll_for_missed().await,
+            },
+            None => watch.rx.recv().await,
+        };
+
+        match received {
+            Some(Ok(event)) => {
+                let now = Instant::now();
+                self.last_fs_event = Some(now);
+                self.quiet_since = now;
+                self.polling_fallback = false;
+                self.handle_fs_event(event);
+                Ok(())
+            }
+            Some(Err(e)) => Err(e.into()),
+            None => Err(anyhow!("File watcher channel closed")),
+        }
     }
 
-    async fn handle_fs_event(&mut self, event: Event) -> Result<()> {
+    async fn poll_for_missed(&mut self) -> Result<()> {
+        let queued = self.queue.len();
+        self.poll().await?;
+
+        let lag_bytes = match &self.file {
+            Some(file) => file.unread_bytes().await.unwrap_or(0),
+            None => 0,
+        };
+        let new_files = self.queue.len() - queued;
+
+        if (lag_bytes > 0 || new_files > 0) && !self.polling_fallback {
+            self.polling_fallback = true;
+            tracing::warn!(
+                "watcher delivered no events for {} but found {} unread bytes and {} new files, falling back to polling",
+                self.base_path.display(),
+                lag_bytes,
+                new_files
+            );
+        }
+
+        Ok(())
+    }
+
+    async fn poll(&mut self) -> Result<()> {
+        let after = self.newest_timestamp().unwrap_or(self.floor);
+        self.catch_up(after).await
+    }
+
+    async fn catch_up(&mut self, after: u64) -> Result<()> {
+        self.dirty = self.file.is_some();
+
+        let newer = files_after(&self.base_path, after).await.unwrap_or_default();
+        if !newer.is_empty() {
+            tracing::info!(
+                "catching up through {} hourly files in {}",
+                newer.len(),
+                self.base_path.display()
+            );
+        }
+
+        self.queue.extend(newer);
+        Ok(())
+    }
+
+    // Only records what the event asks for; the rotation itself happens in `next_event`, so an
+    // event is never lost to an interrupted read.
+    fn handle_fs_event(&mut self, event: Event) {
         if !event.kind.is_create() && !event.kind.is_modify() {
-            return Ok(());
+            return;
         }
 
         for path in event.paths {
@@ -80,65 +513,430 @@ impl Reader {
                 continue;
             }
 
-            let current_path = self.file.as_ref().map(|f| f.path().clone());
-
-            if event.kind.is_create() && is_valid_hourly_file(&path) && self.should_rotate(&path) {
-                self.rotate(path).await?;
-            } else if event.kind.is_modify() {
-                match &current_path {
-                    Some(p) if *p == path => {
-                        self.read_into_pending().await?;
-                    }
-                    None if is_valid_hourly_file(&path) => {
-                        let tracked = TrackedFile::open_at_end(path).await?;
-                        self.file = Some(tracked);
-                    }
-                    _ => {}
-                }
+            if is_valid_hourly_file(&path) && self.should_rotate(&path) {
+                self.rotate_to = Some(path);
+            } else if self.file.as_ref().is_some_and(|f| f.path() == path) {
+                self.dirty = true;
             }
         }
+    }
 
-        Ok(())
+    fn newest_timestamp(&self) -> Option<u64> {
+        match self.queue.back() {
+            Some(queued) => extract_timestamp(queued),
+            None => self.file.as_ref().and_then(|f| extract_timestamp(f.path())),
+        }
     }
 
-    fn should_rotate(&self, new_path: &PathBuf) -> bool {
-        let Some(current) = &self.file else {
-            return true;
+    fn should_rotate(&self, new_path: &Path) -> bool {
+        let newest = match &self.rotate_to {
+            Some(pending) => extract_timestamp(pending),
+            None if self.file.is_none() && self.queue.is_empty() => return true,
+            None => self.newest_timestamp(),
         };
-        match (extract_timestamp(current.path()), extract_timestamp(new_path)) {
+        match (newest, extract_timestamp(new_path)) {
             (Some(cur), Some(new)) => new > cur,
             (None, Some(_)) => true,
             _ => false,
         }
     }
 
-    async fn rotate(&mut self, new_path: PathBuf) -> Result<()> {
-        self.read_into_pending().await?;
+    async fn advance_to(&mut self, new_path: PathBuf) -> Result<()> {
+        let (Some(current), Some(target)) = (self.newest_timestamp(), extract_timestamp(&new_path))
+        else {
+            self.rotate_to = None;
+            self.queue.push_back(new_path);
+            return Ok(());
+        };
 
-        if let Some(old_file) = &mut self.file {
-            old_file.discard_partial();
+        let skipped: Vec<PathBuf> = files_after(&self.base_path, current)
+            .await
+            .unwrap_or_default()
+            .into_iter()
+            .filter(|p| extract_timestamp(p).is_some_and(|ts| ts < target))
+            .collect();
+
+        if !skipped.is_empty() {
+            tracing::warn!(
+                "rotation from {} to {} skipped {} hourly files, draining them first",
+                current,
+                target,
+                skipped.len()
+            );
         }
 
-        let tracked = TrackedFile::open(new_path).await?;
-        self.file = Some(tracked);
+        self.rotate_to = None;
+        self.queue.extend(skipped);
+        self.queue.push_back(new_path);
+        Ok(())
+    }
 
-        self.read_into_pending().await?;
+    // `new_path` stays at the front of the queue until it is open, so an interrupted switch is
+    // simply retried. A file that vanished while queued, usually compressed in the meantime, is
+    // replaced by its sibling or skipped.
+    async fn switch_to(&mut self, new_path: PathBuf) -> Result<()> {
+        if let Some(old_file) = &mut self.file
+            && old_file.unread_bytes().await.unwrap_or(0) > 0
+        {
+            self.dirty = true;
+            return Ok(());
+        }
+
+        let opened = match TrackedFile::open(new_path.clone()).await {
+            Ok(opened) => opened,
+            Err(e) if is_not_found(&e) => {
+                self.queue.pop_front();
+                match find_sibling(&new_path).await {
+                    Some(sibling) => {
+                        tracing::warn!(
+                            "{} is gone, reading {} instead",
+                            new_path.display(),
+                            sibling.display()
+                        );
+                        self.queue.push_front(sibling);
+                    }
+                    None => tracing::warn!("{} is gone, skipping it", new_path.display()),
+                }
+                return Ok(());
+            }
+            Err(e) => return Err(e),
+        };
+        if let Some(old_file) = &mut self.file {
+            old_file.discard_partial();
+        }
+        self.queue.pop_front();
+        self.file = Some(opened);
+        self.dirty = true;
         Ok(())
     }
 
-    async fn read_into_pending(&mut self) -> Result<()> {
+    async fn read_batch(&mut self) -> Result<()> {
         let Some(file) = &mut self.file else {
+            self.dirty = false;
             return Ok(());
         };
 
-        let source = file.path().clone();
-        for line in file.read_lines().await? {
-            self.pending.push_back(FileEvent {
-                source: source.clone(),
-                line,
-            });
+        let source: Arc<Path> = Arc::from(file.path());
+        let file_id = Some(file.id());
+        let hour = extract_timestamp(&source);
+        let read_at = Utc::now();
+        let limit = self.config.batch_size.max(1);
+        let pending = &mut self.pending;
+
+        let read = file
+            .read_lines(limit, self.config.max_line_len, &mut self.spare, |line| {
+                let position = |span: Span| Position {
+                    path: source.clone(),
+                    hour,
+                    offset: span.start,
+                    next_offset: span.end,
+                    line: span.number,
+                    read_at,
+                    file_id,
+                };
+                pending.push_back(match line {
+                    Line::Text { text, span } => Pending::Line(FileEvent {
+                        line: text,
+                        position: position(span),
+                    }),
+                    Line::Oversize { len, span } => Pending::TooLong(LineTooLong {
+                        position: position(span),
+                        len,
+                    }),
+                });
+            })
+            .await?;
+
+        if read > 0 {
+            self.last_line = Some(Instant::now());
+        }
+        self.dirty = read >= limit;
+
+        if !self.dirty
+            && let Some(kind) = file.check().await?
+        {
+            self.dirty = true;
+            self.pending.push_back(Pending::Reset(FileReset {
+                path: source.to_path_buf(),
+                kind,
+            }));
         }
 
         Ok(())
     }
-}
\ No newline at end of file
+}
+
+fn is_not_found(e: &anyhow::Error) -> bool {
+    e.downcast_ref::<std::io::Error>()
+        .is_some_and(|e| e.kind() == std::io::ErrorKind::NotFound)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    struct TempDir(PathBuf);
+
+    impl TempDir {
+        fn new() -> Self {
+            let path = std::env::temp_dir().join(format!("hl-reader-{}", uuid::Uuid::new_v4()));
+            std::fs::create_dir_all(path.join("hourly/20250101")).unwrap();
+            Self(path)
+        }
+
+        fn write_hour(&self, hour: u64, lines: std::ops::Range<usize>) -> PathBuf {
+            let path = self.0.join(format!("hourly/20250101/{}", hour));
+            let body: String = lines.map(|i| format!("line {}\n", i)).collect();
+            std::fs::write(&path, body).unwrap();
+            path
+        }
+
+        // Writes the file elsewhere and renames it into place, as a node rotating hours does.
+        fn rename_hour(&self, hour: u64, lines: std::ops::Range<usize>) -> PathBuf {
+            let staged = self.0.join(format!("staged-{}", hour));
+            let body: String = lines.map(|i| format!("line {}\n", i)).collect();
+            std::fs::write(&staged, body).unwrap();
+            let path = self.0.join(format!("hourly/20250101/{}", hour));
+            std::fs::rename(&staged, &path).unwrap();
+            path
+        }
+    }
+
+    impl TempDir {
+        // Gzips an hour next to itself and removes the original, as an archiving job would.
+        async fn compress_hour(&self, hour: u64) -> PathBuf {
+            use tokio::io::AsyncWriteExt;
+
+            let path = self.0.join(format!("hourly/20250101/{}", hour));
+            let compressed = path.with_file_name(format!("{}.gz", hour));
+            let mut encoder = async_compression::tokio::write::GzipEncoder::new(Vec::new());
+            encoder.write_all(&std::fs::read(&path).unwrap()).await.unwrap();
+            encoder.shutdown().await.unwrap();
+            std::fs::write(&compressed, encoder.into_inner()).unwrap();
+            std::fs::remove_file(&path).unwrap();
+            compressed
+        }
+    }
+
+    impl Drop for TempDir {
+        fn drop(&mut self) {
+            let _ = std::fs::remove_dir_all(&self.0);
+        }
+    }
+
+    async fn drain(reader: &mut Reader) -> Vec<String> {
+        let mut lines = Vec::new();
+        loop {
+            match reader.next_event().await {
+                Ok(event) => lines.push(event.line),
+                Err(e) if e.is::<EndOfStream>() => return lines,
+                Err(e) => panic!("{}", e),
+            }
+        }
+    }
+
+    // For readers that follow live, where running dry means waiting rather than `EndOfStream`.
+    async fn take(reader: &mut Reader, n: usize) -> Vec<String> {
+        let mut lines = Vec::new();
+        while lines.len() < n {
+            match timeout(Duration::from_secs(10), reader.next_event()).await {
+                Ok(Ok(event)) => lines.push(event.line),
+                Ok(Err(e)) => panic!("{}", e),
+                Err(_) => panic!("stalled after {:?}", lines),
+            }
+        }
+        lines
+    }
+
+    fn numbered(lines: std::ops::Range<usize>) -> Vec<String> {
+        lines.map(|i| format!("line {}", i)).collect()
+    }
+
+    #[tokio::test]
+    async fn interrupted_reads_lose_no_lines() {
+        let dir = TempDir::new();
+        dir.write_hour(0, 0..2000);
+
+        let config = ReaderConfig {
+            batch_size: 64,
+            ..Default::default()
+        };
+        let mut reader = Reader::backfill(dir.0.clone(), 2025010100, Some(2025010100))
+            .await
+            .unwrap()
+            .with_config(config);
+
+        // Poll once and drop the read whenever it would wait, like a `select!` losing to a timer.
+        let mut lines = Vec::new();
+        let mut interrupted = 0;
+        loop {
+            match futures::FutureExt::now_or_never(reader.next_event()) {
+                Some(Ok(event)) => lines.push(event.line),
+                Some(Err(e)) if e.is::<EndOfStream>() => break,
+                Some(Err(e)) => panic!("{}", e),
+                None => {
+                    interrupted += 1;
+                    tokio::task::yield_now().await;
+                }
+            }
+        }
+
+        assert!(interrupted > 0);
+        assert_eq!(lines, numbered(0..2000));
+
+        let checkpoint = reader.checkpoint().unwrap();
+        assert_eq!(checkpoint.line, Some(2000));
+        assert_eq!(drain(&mut reader).await, Vec::<String>::new());
+    }
+
+    async fn follow_rotation(config: ReaderConfig) -> Vec<String> {
+        let dir = TempDir::new();
+        dir.write_hour(10, 0..5);
+
+        let mut reader = Reader::new(dir.0.clone()).await.unwrap().with_config(config);
+
+        // Let the reader go live and settle on hour 10 before the node rotates.
+        assert!(timeout(Duration::from_millis(200), reader.next_event()).await.is_err());
+
+        dir.rename_hour(11, 5..10);
+        dir.rename_hour(12, 10..15);
+
+        take(&mut reader, 10).await
+    }
+
+    #[tokio::test]
+    async fn polling_reader_drains_every_rotated_hour() {
+        let lines = follow_rotation(ReaderConfig {
+            watch_mode: WatchMode::Poll {
+                interval: Duration::from_millis(20),
+            },
+            ..Default::default()
+        })
+        .await;
+        assert_eq!(lines, numbered(5..15));
+    }
+
+    #[tokio::test]
+    async fn hybrid_reader_drains_every_rotated_hour() {
+        // A one-slot event buffer overflows on the renames, so the reader has to find the new
+        // hours without their create events.
+        let lines = follow_rotation(ReaderConfig {
+            watch_mode: WatchMode::Hybrid {
+                interval: Duration::from_millis(20),
+                idle: Duration::from_millis(50),
+            },
+            event_buffer: 1,
+            ..Default::default()
+        })
+        .await;
+        assert_eq!(lines, numbered(5..15));
+    }
+
+    #[tokio::test]
+    async fn event_for_a_later_hour_drains_the_hours_before_it() {
+        let dir = TempDir::new();
+        dir.write_hour(10, 0..5);
+
+        let mut reader = Reader::backfill(dir.0.clone(), 2025010110, Some(2025010112)).await.unwrap();
+        assert_eq!(reader.next_event().await.unwrap().line, "line 0");
+
+        // The watcher saw hour 12 arrive but not hour 11.
+        dir.rename_hour(11, 5..10);
+        let latest = dir.rename_hour(12, 10..15);
+        let event = Event::new(notify::EventKind::Create(notify::event::CreateKind::File)).add_path(latest);
+        reader.handle_fs_event(event);
+
+        assert_eq!(drain(&mut reader).await, numbered(1..15));
+    }
+
+    #[tokio::test]
+    async fn checkpoint_for_a_replaced_file_restarts_it() {
+        let dir = TempDir::new();
+        dir.write_hour(10, 0..5);
+
+        let mut reader = Reader::backfill(dir.0.clone(), 2025010110, Some(2025010110)).await.unwrap();
+        assert_eq!(drain(&mut reader).await, numbered(0..5));
+        let checkpoint = reader.checkpoint().unwrap();
+        assert!(checkpoint.file_id.is_some());
+        drop(reader);
+
+        // Replaced while we were down, by a longer file the old offset would land inside.
+        let path = dir.rename_hour(10, 100..110);
+
+        let mut reader = Reader::resume(dir.0.clone(), Some(checkpoint)).await.unwrap();
+        let reset = reader.next_event().await.err().unwrap();
+        assert_eq!(
+            reset.downcast_ref::<FileReset>(),
+            Some(&FileReset {
+                path,
+                kind: ResetKind::Replaced,
+            })
+        );
+        assert_eq!(take(&mut reader, 10).await, numbered(100..110));
+    }
+
+    #[tokio::test]
+    async fn checkpoint_for_the_same_file_resumes_mid_way() {
+        let dir = TempDir::new();
+        dir.write_hour(10, 0..5);
+
+        let mut reader = Reader::backfill(dir.0.clone(), 2025010110, Some(2025010110)).await.unwrap();
+        assert_eq!(drain(&mut reader).await, numbered(0..5));
+        let checkpoint = reader.checkpoint().unwrap();
+        drop(reader);
+
+        let mut file = std::fs::OpenOptions::new().append(true).open(&checkpoint.path).unwrap();
+        std::io::Write::write_all(&mut file, b"line 5\n").unwrap();
+
+        let mut reader = Reader::resume(dir.0.clone(), Some(checkpoint)).await.unwrap();
+        assert_eq!(take(&mut reader, 1).await, numbered(5..6));
+    }
+
+    #[tokio::test]
+    async fn checkpoint_for_a_compressed_file_resumes_from_its_sibling() {
+        let dir = TempDir::new();
+        dir.write_hour(10, 0..5);
+        dir.write_hour(11, 5..10);
+
+        let mut reader = Reader::backfill(dir.0.clone(), 2025010110, Some(2025010110)).await.unwrap();
+        for _ in 0..3 {
+            reader.next_event().await.unwrap();
+        }
+        let checkpoint = reader.checkpoint().unwrap();
+        drop(reader);
+
+        dir.compress_hour(10).await;
+
+        let mut reader = Reader::resume(dir.0.clone(), Some(checkpoint)).await.unwrap();
+        assert_eq!(take(&mut reader, 7).await, numbered(3..10));
+        assert_eq!(reader.checkpoint().unwrap().line, Some(5));
+    }
+
+    #[tokio::test]
+    async fn queued_file_compressed_away_is_read_from_its_sibling() {
+        let dir = TempDir::new();
+        dir.write_hour(0, 0..5);
+        dir.write_hour(1, 5..10);
+        dir.write_hour(2, 10..15);
+
+        let mut reader = Reader::backfill(dir.0.clone(), 2025010100, Some(2025010102)).await.unwrap();
+        dir.compress_hour(1).await;
+
+        assert_eq!(drain(&mut reader).await, numbered(0..15));
+    }
+
+    #[tokio::test]
+    async fn queued_file_deleted_is_skipped() {
+        let dir = TempDir::new();
+        dir.write_hour(0, 0..5);
+        let gone = dir.write_hour(1, 5..10);
+        dir.write_hour(2, 10..15);
+
+        let mut reader = Reader::backfill(dir.0.clone(), 2025010100, Some(2025010102)).await.unwrap();
+        std::fs::remove_file(gone).unwrap();
+
+        let mut expected = numbered(0..5);
+        expected.extend(numbered(10..15));
+        assert_eq!(drain(&mut reader).await, expected);
+    }
+}
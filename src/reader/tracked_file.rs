@@ -1,79 +1,324 @@
-use std::path::PathBuf;
+use std::io::ErrorKind;
+use std::path::{Path, PathBuf};
 
 use anyhow::Result;
-use tokio::fs::File;
-use tokio::io::{AsyncBufReadExt, AsyncSeekExt, BufReader, SeekFrom};
+use tokio::fs::{self, File};
+use tokio::io::{self, AsyncBufReadExt, AsyncReadExt, AsyncSeekExt, SeekFrom};
+
+use crate::reader::checkpoint::{Checkpoint, FileId};
+use crate::reader::compression::{Compression, HourlyReader};
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ResetKind {
+    Truncated,
+    Replaced,
+}
+
+#[derive(Debug, Clone, Copy)]
+pub struct Span {
+    pub start: u64,
+    pub end: u64,
+    pub number: Option<u64>,
+}
+
+pub enum Line {
+    Text { text: String, span: Span },
+    Oversize { len: usize, span: Span },
+}
 
 pub struct TrackedFile {
     path: PathBuf,
-    reader: BufReader<File>,
-    partial: String,
+    reader: HourlyReader,
+    compression: Compression,
+    id: FileId,
+    offset: u64,
+    line_start: u64,
+    line_no: Option<u64>,
+    partial: Vec<u8>,
+    skipped: Option<usize>,
 }
 
 impl TrackedFile {
     pub async fn open(path: PathBuf) -> Result<Self> {
-        let file = File::open(&path).await?;
+        Self::open_at(path, 0, String::new(), Some(0)).await
+    }
+
+    pub async fn open_at_end(path: PathBuf) -> Result<Self> {
+        let compression = Compression::from_path(&path);
+        if compression.is_compressed() {
+            let mut tracked = Self::open_at(path, 0, String::new(), None).await?;
+            tracked.offset = io::copy(&mut tracked.reader, &mut io::sink()).await?;
+            tracked.line_start = tracked.offset;
+            return Ok(tracked);
+        }
+
+        let mut file = File::open(&path).await?;
+        let meta = file.metadata().await?;
+        let offset = file.seek(SeekFrom::End(0)).await?;
         Ok(Self {
             path,
-            reader: BufReader::new(file),
-            partial: String::new(),
+            reader: compression.wrap(file),
+            compression,
+            id: FileId::of(&meta),
+            offset,
+            line_start: offset,
+            line_no: (offset == 0).then_some(0),
+            partial: Vec::new(),
+            skipped: None,
         })
     }
 
-    pub async fn open_at_end(path: PathBuf) -> Result<Self> {
+    pub async fn open_at(
+        path: PathBuf,
+        offset: u64,
+        partial: String,
+        line_no: Option<u64>,
+    ) -> Result<Self> {
+        let compression = Compression::from_path(&path);
         let mut file = File::open(&path).await?;
-        file.seek(SeekFrom::End(0)).await?;
+        let meta = file.metadata().await?;
+        if offset > 0 && !compression.is_compressed() {
+            file.seek(SeekFrom::Start(offset)).await?;
+        }
+
+        let mut reader = compression.wrap(file);
+        if offset > 0 && compression.is_compressed() {
+            let skipped = io::copy(&mut (&mut reader).take(offset), &mut io::sink()).await?;
+            if skipped < offset {
+                anyhow::bail!(
+                    "{} decompresses to {} bytes, cannot resume at offset {}",
+                    path.display(),
+                    skipped,
+                    offset
+                );
+            }
+        }
+
         Ok(Self {
             path,
-            reader: BufReader::new(file),
-            partial: String::new(),
+            reader,
+            compression,
+            id: FileId::of(&meta),
+            offset,
+            line_start: offset.saturating_sub(partial.len() as u64),
+            line_no,
+            partial: partial.into_bytes(),
+            skipped: None,
         })
     }
 
-    pub async fn read_lines(&mut self) -> Result<Vec<String>> {
-        let mut lines = Vec::new();
-        let mut buf = String::new();
+    pub async fn check(&mut self) -> Result<Option<ResetKind>> {
+        let meta = match fs::metadata(&self.path).await {
+            Ok(meta) => meta,
+            Err(e) if e.kind() == ErrorKind::NotFound => return Ok(None),
+            Err(e) => return Err(e.into()),
+        };
+
+        let kind = if FileId::of(&meta) != self.id {
+            ResetKind::Replaced
+        } else if !self.compression.is_compressed() && meta.len() < self.offset {
+            ResetKind::Truncated
+        } else {
+            return Ok(None);
+        };
 
-        loop {
-            buf.clear();
-            let bytes_read = self.reader.read_line(&mut buf).await?;
+        tracing::warn!("{} was {:?} at offset {}, reopening", self.path.display(), kind, self.offset);
+        *self = Self::open(self.path.clone()).await?;
+
+        Ok(Some(kind))
+    }
 
-            if bytes_read == 0 {
+    // Hands each line to `emit` as soon as it is complete, so dropping the future between reads
+    // loses nothing: the offset only ever covers lines that were already emitted.
+    pub async fn read_lines(
+        &mut self,
+        limit: usize,
+        max_line_len: usize,
+        spare: &mut Vec<String>,
+        mut emit: impl FnMut(Line),
+    ) -> Result<usize> {
+        let mut read = 0;
+
+        while read < limit {
+            let buf = self.reader.fill_buf().await?;
+            if buf.is_empty() {
                 break;
             }
 
-            let has_newline = buf.ends_with('\n');
-            let content = buf.trim_end();
+            let (chunk, complete) = match buf.iter().position(|b| *b == b'\n') {
+                Some(i) => (&buf[..i], true),
+                None => (buf, false),
+            };
+            let consumed = chunk.len() + complete as usize;
 
-            if content.is_empty() {
+            match &mut self.skipped {
+                Some(skipped) => *skipped += chunk.len(),
+                None if self.partial.len() + chunk.len() > max_line_len => {
+                    self.skipped = Some(self.partial.len() + chunk.len());
+                    self.partial.clear();
+                }
+                None => self.partial.extend_from_slice(chunk),
+            }
+
+            self.reader.consume(consumed);
+            self.offset += consumed as u64;
+
+            if !complete {
                 continue;
             }
 
-            let full_line = if self.partial.is_empty() {
-                content.to_owned()
-            } else {
-                let combined = std::mem::take(&mut self.partial) + content;
-                combined
+            self.line_no = self.line_no.map(|n| n + 1);
+            let span = Span {
+                start: self.line_start,
+                end: self.offset,
+                number: self.line_no,
             };
+            self.line_start = self.offset;
+
+            if let Some(len) = self.skipped.take() {
+                emit(Line::Oversize { len, span });
+                read += 1;
+                continue;
+            }
 
-            if has_newline {
-                lines.push(full_line);
-            } else {
-                self.partial = full_line;
+            let content = self.partial.trim_ascii_end();
+            if content.is_empty() {
+                self.partial.clear();
+                continue;
             }
+
+            let mut text = spare.pop().unwrap_or_default();
+            text.clear();
+            text.push_str(&String::from_utf8_lossy(content));
+            self.partial.clear();
+
+            emit(Line::Text { text, span });
+            read += 1;
         }
 
-        Ok(lines)
+        Ok(read)
     }
 
     pub fn discard_partial(&mut self) {
-        if !self.partial.is_empty() {
-            tracing::debug!("discarding {} bytes partial line", self.partial.len());
+        if !self.partial.is_empty() || self.skipped.is_some() {
+            tracing::debug!(
+                "discarding {} bytes partial line",
+                self.partial.len() + self.skipped.unwrap_or(0)
+            );
             self.partial.clear();
+            self.skipped = None;
+        }
+    }
+
+    pub async fn unread_bytes(&self) -> Result<u64> {
+        if self.compression.is_compressed() {
+            return Ok(0);
         }
+        let len = fs::metadata(&self.path).await?.len();
+        Ok(len.saturating_sub(self.offset))
+    }
+
+    pub fn checkpoint(&self) -> Checkpoint {
+        match std::str::from_utf8(&self.partial) {
+            Ok(partial) if self.skipped.is_none() => Checkpoint {
+                path: self.path.clone(),
+                offset: self.offset,
+                partial: partial.to_owned(),
+                line: self.line_no,
+                file_id: Some(self.id),
+            },
+            _ => Checkpoint {
+                path: self.path.clone(),
+                offset: self.line_start,
+                partial: String::new(),
+                line: self.line_no,
+                file_id: Some(self.id),
+            },
+        }
+    }
+
+    pub fn id(&self) -> FileId {
+        self.id
     }
 
-    pub fn path(&self) -> &PathBuf {
+    pub fn path(&self) -> &Path {
         &self.path
     }
-}
\ No newline at end of file
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    struct TempFile(PathBuf);
+
+    impl TempFile {
+        fn new(body: &str) -> Self {
+            let path = std::env::temp_dir().join(format!("hl-tracked-{}", uuid::Uuid::new_v4()));
+            std::fs::write(&path, body).unwrap();
+            Self(path)
+        }
+    }
+
+    impl Drop for TempFile {
+        fn drop(&mut self) {
+            let _ = std::fs::remove_file(&self.0);
+            let _ = std::fs::remove_file(self.0.with_extension("staged"));
+        }
+    }
+
+    async fn read_all(file: &mut TrackedFile) -> Vec<String> {
+        let mut lines = Vec::new();
+        file.read_lines(usize::MAX, 1024, &mut Vec::new(), |line| {
+            if let Line::Text { text, .. } = line {
+                lines.push(text);
+            }
+        })
+        .await
+        .unwrap();
+        lines
+    }
+
+    #[tokio::test]
+    async fn appends_are_not_a_reset() {
+        let temp = TempFile::new("a\nb\n");
+        let mut file = TrackedFile::open(temp.0.clone()).await.unwrap();
+        assert_eq!(read_all(&mut file).await, ["a", "b"]);
+
+        std::fs::write(&temp.0, "a\nb\nc\n").unwrap();
+        assert_eq!(file.check().await.unwrap(), None);
+        assert_eq!(read_all(&mut file).await, ["c"]);
+    }
+
+    #[tokio::test]
+    async fn truncation_in_place_rereads_from_the_start() {
+        let temp = TempFile::new("a\nb\n");
+        let mut file = TrackedFile::open(temp.0.clone()).await.unwrap();
+        let id = file.id();
+        assert_eq!(read_all(&mut file).await, ["a", "b"]);
+
+        // Same inode, shorter than what we already read.
+        std::fs::write(&temp.0, "c\n").unwrap();
+        assert_eq!(file.check().await.unwrap(), Some(ResetKind::Truncated));
+        assert_eq!(file.id(), id);
+        assert_eq!(read_all(&mut file).await, ["c"]);
+        assert_eq!(file.checkpoint().offset, 2);
+    }
+
+    #[tokio::test]
+    async fn replacement_by_rename_rereads_the_new_file() {
+        let temp = TempFile::new("a\nb\n");
+        let mut file = TrackedFile::open(temp.0.clone()).await.unwrap();
+        let id = file.id();
+        assert_eq!(read_all(&mut file).await, ["a", "b"]);
+
+        // Longer than the old file, so only the identity gives the replacement away.
+        let staged = temp.0.with_extension("staged");
+        std::fs::write(&staged, "x\ny\nz\n").unwrap();
+        std::fs::rename(&staged, &temp.0).unwrap();
+
+        assert_eq!(file.check().await.unwrap(), Some(ResetKind::Replaced));
+        assert_ne!(file.id(), id);
+        assert_eq!(read_all(&mut file).await, ["x", "y", "z"]);
+    }
+}